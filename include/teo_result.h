@@ -0,0 +1,142 @@
+#ifndef TEO_RESULT_H
+#define TEO_RESULT_H
+
+/* This file is generated by cbindgen. Do not edit it by hand. */
+
+#include <stdint.h>
+#include <stddef.h>
+
+/**
+ * Opaque error handle exposed through the C ABI.
+ *
+ * The strings returned by the accessors are owned by the handle and stay
+ * valid until it is modified or released with `teo_error_free`.
+ */
+typedef struct TeoError TeoError;
+
+/**
+ * Creates an error with a message and an HTTP status code.
+ *
+ * # Safety
+ *
+ * `message` must be null or a valid NUL-terminated string.
+ */
+struct TeoError *teo_error_new(const char *message, uint16_t code);
+
+/**
+ * Creates an error from the JSON produced by `teo_error_to_json`. Returns
+ * null if the input is not a valid error object.
+ *
+ * # Safety
+ *
+ * `json` must be null or a valid NUL-terminated string.
+ */
+struct TeoError *teo_error_from_json(const char *json);
+
+/**
+ * Adds or replaces the error message at `path`.
+ *
+ * # Safety
+ *
+ * `error` must be null or a live handle. `path` and `message` must be null
+ * or valid NUL-terminated strings. Strings previously returned for this
+ * handle are invalidated.
+ */
+void teo_error_insert_error(struct TeoError *error, const char *path, const char *message);
+
+/**
+ * Returns the HTTP status code, or 0 if `error` is null.
+ *
+ * # Safety
+ *
+ * `error` must be null or a live handle.
+ */
+uint16_t teo_error_code(const struct TeoError *error);
+
+/**
+ * Returns the message, or null if `error` is null.
+ *
+ * # Safety
+ *
+ * `error` must be null or a live handle.
+ */
+const char *teo_error_message(const struct TeoError *error);
+
+/**
+ * Returns the number of path errors attached to the error, or 0 if `error`
+ * is null.
+ *
+ * # Safety
+ *
+ * `error` must be null or a live handle.
+ */
+size_t teo_error_errors_len(const struct TeoError *error);
+
+/**
+ * Returns the path of the path error at `index`, or null if out of range
+ * or `error` is null.
+ *
+ * # Safety
+ *
+ * `error` must be null or a live handle.
+ */
+const char *teo_error_errors_key(const struct TeoError *error, size_t index);
+
+/**
+ * Returns the message of the path error at `index`, or null if out of
+ * range or `error` is null.
+ *
+ * # Safety
+ *
+ * `error` must be null or a live handle.
+ */
+const char *teo_error_errors_value(const struct TeoError *error, size_t index);
+
+/**
+ * Serializes the error through `ErrorSerializable`. The returned string
+ * must be released with `teo_string_free`. Returns null if `error` is null.
+ *
+ * # Safety
+ *
+ * `error` must be null or a live handle.
+ */
+char *teo_error_to_json(const struct TeoError *error);
+
+/**
+ * Encodes the error with `envelope::encode`, keeping frames and causes
+ * unless the global redaction policy hides them.
+ * The returned string must be released with `teo_string_free`. Returns
+ * null if `error` is null.
+ *
+ * # Safety
+ *
+ * `error` must be null or a live handle.
+ */
+char *teo_error_to_envelope(const struct TeoError *error);
+
+/**
+ * Decodes an envelope string. Returns null if the input is not an
+ * envelope.
+ *
+ * # Safety
+ *
+ * `input` must be null or a valid NUL-terminated string.
+ */
+struct TeoError *teo_error_from_envelope(const char *input);
+
+/**
+ * # Safety
+ *
+ * `error` must be null or a handle which has not been freed yet.
+ */
+void teo_error_free(struct TeoError *error);
+
+/**
+ * # Safety
+ *
+ * `string` must be null or a string returned by this library which has not
+ * been freed yet.
+ */
+void teo_string_free(char *string);
+
+#endif  /* TEO_RESULT_H */
@@ -0,0 +1,64 @@
+//! Scoped key-values which annotate every error created inside the scope.
+//!
+//! ```
+//! use teo_result::{context, Error};
+//!
+//! let error = context::sync_scope([("request_id", "r1")], || Error::not_found());
+//! assert_eq!(error.annotations().get("request_id").map(String::as_str), Some("r1"));
+//! ```
+//!
+//! With the `tokio` feature, [`scope`] carries the key-values in a task-local
+//! across `.await` points. Nested scopes inherit the outer key-values, and
+//! inner values win.
+
+use std::cell::RefCell;
+use std::sync::Arc;
+use indexmap::IndexMap;
+
+pub type Context = IndexMap<String, String>;
+
+thread_local! {
+    static THREAD_CONTEXT: RefCell<Option<Arc<Context>>> = const { RefCell::new(None) };
+}
+
+#[cfg(feature = "tokio")]
+tokio::task_local! {
+    static TASK_CONTEXT: Arc<Context>;
+}
+
+/// Returns the key-values of the innermost scope. A task-local scope takes
+/// precedence over a thread-local one.
+pub fn current() -> Option<Arc<Context>> {
+    #[cfg(feature = "tokio")]
+    if let Ok(context) = TASK_CONTEXT.try_with(Arc::clone) {
+        return Some(context);
+    }
+    THREAD_CONTEXT.with(|context| context.borrow().clone())
+}
+
+fn merged<K: Into<String>, V: Into<String>>(values: impl IntoIterator<Item = (K, V)>) -> Arc<Context> {
+    let mut context = current().map(|context| (*context).clone()).unwrap_or_default();
+    context.extend(values.into_iter().map(|(k, v)| (k.into(), v.into())));
+    Arc::new(context)
+}
+
+struct Restore(Option<Arc<Context>>);
+
+impl Drop for Restore {
+    fn drop(&mut self) {
+        THREAD_CONTEXT.with(|context| *context.borrow_mut() = self.0.take());
+    }
+}
+
+/// Runs `f` with the key-values attached to the current thread.
+pub fn sync_scope<K: Into<String>, V: Into<String>, R>(values: impl IntoIterator<Item = (K, V)>, f: impl FnOnce() -> R) -> R {
+    let context = merged(values);
+    let _restore = Restore(THREAD_CONTEXT.with(|current| current.replace(Some(context))));
+    f()
+}
+
+/// Runs `future` with the key-values attached to the current task.
+#[cfg(feature = "tokio")]
+pub async fn scope<K: Into<String>, V: Into<String>, F: std::future::Future>(values: impl IntoIterator<Item = (K, V)>, future: F) -> F::Output {
+    TASK_CONTEXT.scope(merged(values), future).await
+}
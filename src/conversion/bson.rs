@@ -1,8 +1,80 @@
+use bson::{doc, Bson, Document};
 use bson::oid::Error as OidError;
-use crate::Error;
+use crate::{Error, Lossless, Result};
 
 impl From<OidError> for Error {
     fn from(value: OidError) -> Self {
         Self::new(value.to_string())
     }
 }
+
+impl From<bson::ser::Error> for Error {
+    fn from(value: bson::ser::Error) -> Self {
+        Self::new(format!("bson encode error: {}", value))
+    }
+}
+
+impl From<bson::de::Error> for Error {
+    fn from(value: bson::de::Error) -> Self {
+        Self::new(format!("bson decode error: {}", value))
+    }
+}
+
+// MongoDB rejects dotted field names on older servers, so maps keyed by
+// paths or arbitrary names are stored as arrays of two-field entries
+const MAPS: [(&str, &str, &str); 3] = [
+    ("errors", "path", "message"),
+    ("annotations", "key", "value"),
+    ("headers", "name", "value"),
+];
+
+fn maps_to_arrays(document: &mut Document) {
+    for (field, key_name, value_name) in MAPS {
+        if let Some(map) = document.get_mut(field) {
+            if let Bson::Document(entries) = std::mem::take(map) {
+                *map = Bson::Array(entries.into_iter().map(|(key, value)| Bson::Document(doc! { key_name: key, value_name: value })).collect());
+            }
+        }
+    }
+    if let Some(Bson::Document(cause)) = document.get_mut("cause") {
+        maps_to_arrays(cause);
+    }
+}
+
+fn maps_from_arrays(document: &mut Document) {
+    for (field, key_name, value_name) in MAPS {
+        if let Some(map) = document.get_mut(field) {
+            if let Bson::Array(entries) = std::mem::take(map) {
+                *map = Bson::Document(entries.into_iter().filter_map(|entry| match entry {
+                    Bson::Document(mut entry) => match (entry.remove(key_name), entry.remove(value_name)) {
+                        (Some(Bson::String(key)), Some(value)) => Some((key, value)),
+                        _ => None,
+                    },
+                    _ => None,
+                }).collect());
+            }
+        }
+    }
+    if let Some(Bson::Document(cause)) = document.get_mut("cause") {
+        maps_from_arrays(cause);
+    }
+}
+
+impl Error {
+
+    /// The document carries every field, see [`Lossless`], and additionally
+    /// `title`, the inferred title of the status code, which is ignored when
+    /// decoding. Fails if a value does not
+    /// fit into BSON, such as a retry-after hint beyond `i64::MAX` seconds.
+    pub fn to_bson_document(&self) -> Result<Document> {
+        let mut document = bson::to_document(&Lossless(self))?;
+        maps_to_arrays(&mut document);
+        document.insert("title", self.inferred_title().as_ref());
+        Ok(document)
+    }
+
+    pub fn from_bson_document(mut document: Document) -> Result<Self> {
+        maps_from_arrays(&mut document);
+        Ok(bson::from_document(document)?)
+    }
+}
@@ -0,0 +1,29 @@
+use std::fmt::Debug;
+use crate::{Error, ErrorSerializable, Result};
+
+impl<T: Debug> From<ciborium::ser::Error<T>> for Error {
+    fn from(value: ciborium::ser::Error<T>) -> Self {
+        Error::new(format!("cbor encode error: {}", value))
+    }
+}
+
+impl<T: Debug> From<ciborium::de::Error<T>> for Error {
+    fn from(value: ciborium::de::Error<T>) -> Self {
+        Error::new(format!("cbor decode error: {}", value))
+    }
+}
+
+impl Error {
+
+    pub fn to_cbor(&self) -> Result<Vec<u8>> {
+        self.ensure_incident_id();
+        let mut bytes = Vec::new();
+        ciborium::into_writer(&ErrorSerializable::unredacted(self), &mut bytes)?;
+        Ok(bytes)
+    }
+
+    pub fn from_cbor(bytes: &[u8]) -> Result<Self> {
+        let serializable: ErrorSerializable = ciborium::from_reader(bytes)?;
+        Ok(Error::from(serializable))
+    }
+}
@@ -0,0 +1,212 @@
+use std::ffi::{c_char, CStr, CString};
+use std::ptr;
+use indexmap::IndexMap;
+use crate::{envelope, Error, ErrorSerializable};
+
+/// Opaque error handle exposed through the C ABI.
+///
+/// The strings returned by the accessors are owned by the handle and stay
+/// valid until it is modified or released with `teo_error_free`.
+pub struct TeoError {
+    error: Error,
+    message: CString,
+    errors: Vec<(CString, CString)>,
+}
+
+impl TeoError {
+
+    /// Moves an error onto the heap and returns the handle for C callers.
+    pub fn into_raw(error: Error) -> *mut TeoError {
+        Box::into_raw(Box::new(Self::build(error)))
+    }
+
+    /// Takes back ownership of a handle created by `into_raw`.
+    ///
+    /// # Safety
+    ///
+    /// `handle` must be a non-null pointer returned by this library which
+    /// has not been freed yet.
+    pub unsafe fn from_raw(handle: *mut TeoError) -> Error {
+        Box::from_raw(handle).error
+    }
+
+    pub fn error(&self) -> &Error {
+        &self.error
+    }
+
+    fn build(error: Error) -> Self {
+        Self {
+            message: c_string(error.message()),
+            errors: error.errors().map(|errors| errors.iter().map(|(k, v)| (c_string(k), c_string(v))).collect()).unwrap_or_default(),
+            error,
+        }
+    }
+}
+
+fn c_string(value: &str) -> CString {
+    CString::new(value.replace('\0', "")).unwrap()
+}
+
+unsafe fn string_arg(value: *const c_char) -> Option<String> {
+    if value.is_null() {
+        None
+    } else {
+        Some(CStr::from_ptr(value).to_string_lossy().into_owned())
+    }
+}
+
+/// Creates an error with a message and an HTTP status code.
+///
+/// # Safety
+///
+/// `message` must be null or a valid NUL-terminated string.
+#[no_mangle]
+pub unsafe extern "C" fn teo_error_new(message: *const c_char, code: u16) -> *mut TeoError {
+    TeoError::into_raw(Error::new_with_code(string_arg(message).unwrap_or_default(), code))
+}
+
+/// Creates an error from the JSON produced by `teo_error_to_json`. Returns
+/// null if the input is not a valid error object.
+///
+/// # Safety
+///
+/// `json` must be null or a valid NUL-terminated string.
+#[no_mangle]
+pub unsafe extern "C" fn teo_error_from_json(json: *const c_char) -> *mut TeoError {
+    let Some(json) = string_arg(json) else {
+        return ptr::null_mut();
+    };
+    let Ok(serializable) = serde_json::from_str::<ErrorSerializable>(json.as_str()) else {
+        return ptr::null_mut();
+    };
+    TeoError::into_raw(Error::from(serializable))
+}
+
+/// Adds or replaces the error message at `path`.
+///
+/// # Safety
+///
+/// `error` must be null or a live handle. `path` and `message` must be null
+/// or valid NUL-terminated strings. Strings previously returned for this
+/// handle are invalidated.
+#[no_mangle]
+pub unsafe extern "C" fn teo_error_insert_error(error: *mut TeoError, path: *const c_char, message: *const c_char) {
+    let (Some(handle), Some(path), Some(message)) = (error.as_mut(), string_arg(path), string_arg(message)) else {
+        return;
+    };
+    let mut inner = std::mem::replace(&mut handle.error, Error::new(""));
+    inner.errors.get_or_insert_with(IndexMap::new).insert(path, message);
+    *handle = TeoError::build(inner);
+}
+
+/// Returns the HTTP status code, or 0 if `error` is null.
+///
+/// # Safety
+///
+/// `error` must be null or a live handle.
+#[no_mangle]
+pub unsafe extern "C" fn teo_error_code(error: *const TeoError) -> u16 {
+    error.as_ref().map_or(0, |handle| handle.error.code())
+}
+
+/// Returns the message, or null if `error` is null.
+///
+/// # Safety
+///
+/// `error` must be null or a live handle.
+#[no_mangle]
+pub unsafe extern "C" fn teo_error_message(error: *const TeoError) -> *const c_char {
+    error.as_ref().map_or(ptr::null(), |handle| handle.message.as_ptr())
+}
+
+/// Returns the number of path errors attached to the error, or 0 if `error`
+/// is null.
+///
+/// # Safety
+///
+/// `error` must be null or a live handle.
+#[no_mangle]
+pub unsafe extern "C" fn teo_error_errors_len(error: *const TeoError) -> usize {
+    error.as_ref().map_or(0, |handle| handle.errors.len())
+}
+
+/// Returns the path of the path error at `index`, or null if out of range
+/// or `error` is null.
+///
+/// # Safety
+///
+/// `error` must be null or a live handle.
+#[no_mangle]
+pub unsafe extern "C" fn teo_error_errors_key(error: *const TeoError, index: usize) -> *const c_char {
+    error.as_ref().and_then(|handle| handle.errors.get(index)).map_or(ptr::null(), |(k, _)| k.as_ptr())
+}
+
+/// Returns the message of the path error at `index`, or null if out of
+/// range or `error` is null.
+///
+/// # Safety
+///
+/// `error` must be null or a live handle.
+#[no_mangle]
+pub unsafe extern "C" fn teo_error_errors_value(error: *const TeoError, index: usize) -> *const c_char {
+    error.as_ref().and_then(|handle| handle.errors.get(index)).map_or(ptr::null(), |(_, v)| v.as_ptr())
+}
+
+/// Serializes the error through `ErrorSerializable`. The returned string
+/// must be released with `teo_string_free`. Returns null if `error` is null.
+///
+/// # Safety
+///
+/// `error` must be null or a live handle.
+#[no_mangle]
+pub unsafe extern "C" fn teo_error_to_json(error: *const TeoError) -> *mut c_char {
+    error.as_ref().map_or(ptr::null_mut(), |handle| c_string(ErrorSerializable::error_string(&handle.error).as_str()).into_raw())
+}
+
+/// Encodes the error with `envelope::encode`, keeping frames and causes
+/// unless the global redaction policy hides them.
+/// The returned string must be released with `teo_string_free`. Returns
+/// null if `error` is null.
+///
+/// # Safety
+///
+/// `error` must be null or a live handle.
+#[no_mangle]
+pub unsafe extern "C" fn teo_error_to_envelope(error: *const TeoError) -> *mut c_char {
+    error.as_ref().map_or(ptr::null_mut(), |handle| c_string(envelope::encode(&handle.error).as_str()).into_raw())
+}
+
+/// Decodes an envelope string. Returns null if the input is not an
+/// envelope.
+///
+/// # Safety
+///
+/// `input` must be null or a valid NUL-terminated string.
+#[no_mangle]
+pub unsafe extern "C" fn teo_error_from_envelope(input: *const c_char) -> *mut TeoError {
+    match string_arg(input).as_deref().and_then(envelope::decode) {
+        Some(error) => TeoError::into_raw(error),
+        None => ptr::null_mut(),
+    }
+}
+
+/// # Safety
+///
+/// `error` must be null or a handle which has not been freed yet.
+#[no_mangle]
+pub unsafe extern "C" fn teo_error_free(error: *mut TeoError) {
+    if !error.is_null() {
+        drop(Box::from_raw(error));
+    }
+}
+
+/// # Safety
+///
+/// `string` must be null or a string returned by this library which has not
+/// been freed yet.
+#[no_mangle]
+pub unsafe extern "C" fn teo_string_free(string: *mut c_char) {
+    if !string.is_null() {
+        drop(CString::from_raw(string));
+    }
+}
@@ -1,6 +1,7 @@
-use http::header::{InvalidHeaderName, InvalidHeaderValue, ToStrError};
+use http::header::{InvalidHeaderName, InvalidHeaderValue, ToStrError, CONTENT_TYPE, RETRY_AFTER};
 use http::uri::InvalidUri;
-use crate::Error;
+use http::{HeaderName, HeaderValue, Response, StatusCode};
+use crate::{Error, ErrorSerializable};
 
 impl From<ToStrError> for Error {
     fn from(_: ToStrError) -> Self {
@@ -24,4 +25,30 @@ impl From<InvalidUri> for Error {
     fn from(_: InvalidUri) -> Self {
         Error::new("Invalid URI")
     }
-}
\ No newline at end of file
+}
+
+/// Builds a JSON response from the public form of the error. A retry-after
+/// hint is sent as the `Retry-After` header in seconds, rounded up, followed
+/// by the error's own headers. Headers which aren't valid HTTP are skipped,
+/// as are `Content-Type` and, if the error has a retry-after hint,
+/// `Retry-After`, so that they always match the body and the hint.
+impl From<Error> for Response<String> {
+    fn from(value: Error) -> Self {
+        let mut response = Response::new(ErrorSerializable::error_string(&value));
+        *response.status_mut() = StatusCode::from_u16(value.code).unwrap_or(StatusCode::INTERNAL_SERVER_ERROR);
+        let headers = response.headers_mut();
+        headers.insert(CONTENT_TYPE, HeaderValue::from_static("application/json"));
+        if let Some(retry_after) = value.retry_after_secs() {
+            headers.insert(RETRY_AFTER, HeaderValue::from(retry_after));
+        }
+        for (name, header) in value.headers() {
+            if let (Ok(name), Ok(header)) = (HeaderName::try_from(name), HeaderValue::try_from(header)) {
+                if name == CONTENT_TYPE || (name == RETRY_AFTER && value.retry_after().is_some()) {
+                    continue;
+                }
+                headers.insert(name, header);
+            }
+        }
+        response
+    }
+}
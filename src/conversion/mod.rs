@@ -15,4 +15,14 @@ mod cookie;
 #[cfg(feature = "http")]
 mod http;
 #[cfg(feature = "bson")]
-mod bson;
\ No newline at end of file
+mod bson;
+#[cfg(feature = "wasm-bindgen")]
+mod wasm_bindgen;
+#[cfg(feature = "rmp-serde")]
+mod rmp_serde;
+#[cfg(feature = "ciborium")]
+mod ciborium;
+#[cfg(feature = "prost")]
+mod prost;
+#[cfg(feature = "ffi")]
+pub(crate) mod ffi;
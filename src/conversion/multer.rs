@@ -2,6 +2,6 @@ use crate::Error;
 
 impl From<multer::Error> for Error {
     fn from(value: multer::Error) -> Self {
-        Error::invalid_request_message(format!("multipart/form-data error: {}", value.to_string()))
+        Error::invalid_request_message(format!("multipart/form-data error: {}", value))
     }
 }
\ No newline at end of file
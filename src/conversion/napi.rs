@@ -1,55 +1,88 @@
-use std::sync::Arc;
-use napi::Error;
-use crate::error::ErrorSerializable;
-
-fn build_from_error_serializable(value: Error, error_serializable: ErrorSerializable) -> crate::Error {
-    crate::Error {
-        code: error_serializable.code,
-        message: error_serializable.message.to_string(),
-        errors: if let Some(object) = error_serializable.errors.as_object() {
-            Some(object.iter().map(|(k, v)| (k.to_string(), v.as_str().unwrap().to_string())).collect())
-        } else {
-            None
-        },
-        platform_native_object: Some(Arc::new(value)),
-    }
-}
+use napi::{Error, JsObject, JsUnknown, NapiRaw, NapiValue, ValueType, sys};
+use napi::bindgen_prelude::{FromNapiValue, ToNapiValue};
+use crate::envelope;
+use crate::frame::{parse_js_stack, MAX_CAUSE_DEPTH};
 
 impl From<Error> for crate::Error {
     fn from(value: Error) -> Self {
-        if value.status.as_ref() == "GenericFailure" && (value.reason.starts_with("TeoError: ") || value.reason.starts_with("Error: TeoError: ")) {
-            let json_string = if let Some(string) = value.reason.strip_prefix("Error: TeoError: ") {
-                string
-            } else {
-                value.reason.strip_prefix("TeoError: ").unwrap()
-            };
-            let error_serializable: Result<ErrorSerializable, serde_json::Error> = serde_json::from_str(json_string);
-            match error_serializable {
-                Ok(error_serializable) => {
-                    build_from_error_serializable(value, error_serializable)
-                }
-                Err(_) => {
-                    let mut result = crate::Error::new(value.reason.as_str());
-                    result.assign_platform_native_object(value);
-                    result
-                }
-            }
+        if value.status.as_ref() == "GenericFailure" && envelope::is_envelope(value.reason.as_str()) {
+            let mut result = envelope::decode(value.reason.as_str()).unwrap_or_else(|| crate::Error::new(value.reason.as_str()));
+            result.assign_platform_native_object(value);
+            result
         } else {
-            let mut error = crate::Error::new(value.reason.as_str());
+            // the reason may carry a stack when it was built from `error.stack`
+            let (message, frames) = match value.reason.split_once("\n    at ") {
+                Some((message, _)) => (message.to_owned(), parse_js_stack(value.reason.as_str())),
+                None => (value.reason.clone(), Vec::new()),
+            };
+            let mut error = crate::Error::new(message);
+            error.assign_frames(frames);
             error.assign_platform_native_object(value);
             error
         }
     }
 }
 
+/// `napi::Error` carries only a status and a reason, so the cause travels
+/// inside the envelope: the thrown JavaScript error has no ES2022 `cause`,
+/// but converting it back restores the cause. Return the error as a value,
+/// which goes through `ToNapiValue`, to set `cause` on the JavaScript side.
 impl From<crate::Error> for Error {
     fn from(value: crate::Error) -> Self {
         if let Some(napi_error) = value.platform_native_object::<Error>() {
             // contains one native error, use it
             napi_error.clone()
         } else {
-            let message = format!("TeoError: {}", ErrorSerializable::error_string(&value));
-            Error::new(napi::Status::GenericFailure, message)
+            Error::new(napi::Status::GenericFailure, envelope::encode(&value))
         }
     }
-}
\ No newline at end of file
+}
+
+fn error_from_js_value(value: JsUnknown, depth: usize) -> crate::Error {
+    let mut stack = None;
+    let mut cause = None;
+    if let Ok(ValueType::Object) = value.get_type() {
+        let object: JsObject = unsafe { value.cast() };
+        stack = object.get_named_property::<Option<String>>("stack").ok().flatten();
+        if depth < MAX_CAUSE_DEPTH {
+            if let Ok(js_cause) = object.get_named_property::<JsUnknown>("cause") {
+                if !matches!(js_cause.get_type(), Ok(ValueType::Undefined) | Ok(ValueType::Null)) {
+                    cause = Some(error_from_js_value(js_cause, depth + 1));
+                }
+            }
+        }
+    }
+    let mut error = crate::Error::from(Error::from(value));
+    // frames and cause decoded from an envelope take precedence
+    if error.frames().is_empty() {
+        if let Some(stack) = stack {
+            error.assign_frames(parse_js_stack(stack.as_str()));
+        }
+    }
+    if let Some(cause) = cause.filter(|_| error.cause().is_none()) {
+        error.assign_cause(cause);
+    }
+    error
+}
+
+impl FromNapiValue for crate::Error {
+    unsafe fn from_napi_value(env: sys::napi_env, napi_val: sys::napi_value) -> napi::Result<Self> {
+        Ok(error_from_js_value(JsUnknown::from_raw_unchecked(env, napi_val), 0))
+    }
+}
+
+impl ToNapiValue for crate::Error {
+    unsafe fn to_napi_value(env: sys::napi_env, mut val: Self) -> napi::Result<sys::napi_value> {
+        let cause = val.take_cause();
+        let napi_val = Error::to_napi_value(env, Error::from(val))?;
+        if let Some(cause) = cause {
+            let mut object = JsObject::from_raw_unchecked(env, napi_val);
+            if !object.has_named_property("cause")? {
+                let js_cause = JsUnknown::from_raw_unchecked(env, crate::Error::to_napi_value(env, cause)?);
+                object.set_named_property("cause", js_cause)?;
+            }
+            return Ok(object.raw());
+        }
+        Ok(napi_val)
+    }
+}
@@ -0,0 +1,118 @@
+use prost::Message;
+use crate::{Error, Result};
+use crate::rpc::{Any, BadRequest, ErrorInfo, FieldViolation, Status, BAD_REQUEST_TYPE_URL, ERROR_INFO_TYPE_URL};
+
+const ERROR_INFO_DOMAIN: &str = "teodev.io";
+
+const HTTP_STATUS_KEY: &str = "http_status";
+
+fn grpc_code(http_code: u16) -> i32 {
+    match http_code {
+        200..=299 => 0,
+        400 => 3,
+        401 => 16,
+        403 => 7,
+        404 => 5,
+        409 => 10,
+        412 => 9,
+        429 => 8,
+        499 => 1,
+        501 => 12,
+        503 => 14,
+        504 => 4,
+        code if code >= 500 => 13,
+        code if code >= 400 => 9,
+        _ => 2,
+    }
+}
+
+fn http_code(grpc_code: i32) -> u16 {
+    match grpc_code {
+        0 => 200,
+        1 => 499,
+        3 | 9 | 11 => 400,
+        4 => 504,
+        5 => 404,
+        6 | 10 => 409,
+        7 => 403,
+        8 => 429,
+        12 => 501,
+        14 => 503,
+        16 => 401,
+        _ => 500,
+    }
+}
+
+// `ErrorInfo.reason` must match `[A-Z][A-Z0-9_]+[A-Z0-9]`
+fn reason(error: &Error) -> String {
+    match error.status_title() {
+        Some(_) => error.inferred_title_words().join("_").to_ascii_uppercase(),
+        None => format!("HTTP_{}", error.code),
+    }
+}
+
+impl From<&Error> for Status {
+    fn from(value: &Error) -> Self {
+        let mut details = vec![Any {
+            type_url: ERROR_INFO_TYPE_URL.to_owned(),
+            value: ErrorInfo {
+                reason: reason(value),
+                domain: ERROR_INFO_DOMAIN.to_owned(),
+                metadata: [(HTTP_STATUS_KEY.to_owned(), value.code.to_string())].into(),
+            }.encode_to_vec(),
+        }];
+        if let Some(errors) = value.errors() {
+            details.push(Any {
+                type_url: BAD_REQUEST_TYPE_URL.to_owned(),
+                value: BadRequest {
+                    field_violations: errors.iter().map(|(k, v)| FieldViolation { field: k.clone(), description: v.clone() }).collect(),
+                }.encode_to_vec(),
+            });
+        }
+        Status {
+            code: grpc_code(value.code),
+            message: value.message.clone(),
+            details,
+        }
+    }
+}
+
+impl From<Status> for Error {
+    fn from(value: Status) -> Self {
+        let mut code = http_code(value.code);
+        let mut errors = None;
+        for detail in value.details {
+            match detail.type_url.as_str() {
+                ERROR_INFO_TYPE_URL => if let Ok(info) = ErrorInfo::decode(detail.value.as_slice()) {
+                    if let Some(http_status) = info.metadata.get(HTTP_STATUS_KEY).and_then(|status| status.parse().ok()) {
+                        code = http_status;
+                    }
+                },
+                BAD_REQUEST_TYPE_URL => if let Ok(bad_request) = BadRequest::decode(detail.value.as_slice()) {
+                    errors = Some(bad_request.field_violations.into_iter().map(|violation| (violation.field, violation.description)).collect());
+                },
+                _ => (),
+            }
+        }
+        let mut error = Error::decoded(value.message, code);
+        error.errors = errors;
+        error
+    }
+}
+
+impl From<prost::DecodeError> for Error {
+    fn from(value: prost::DecodeError) -> Self {
+        Error::new(format!("protobuf decode error: {}", value))
+    }
+}
+
+impl Error {
+
+    pub fn to_protobuf(&self) -> Vec<u8> {
+        Status::from(self).encode_to_vec()
+    }
+
+    pub fn from_protobuf(bytes: &[u8]) -> Result<Self> {
+        Ok(Error::from(Status::decode(bytes)?))
+    }
+}
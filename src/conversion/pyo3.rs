@@ -1,55 +1,106 @@
 use indexmap::IndexMap;
 use pyo3::{PyErr, import_exception, Python, PyObject, Bound, IntoPyObjectExt};
-use pyo3::types::{PyDict, PyType, PyAnyMethods, PyDictMethods};
-use crate::Error;
+use pyo3::types::{PyAny, PyDict, PyType, PyAnyMethods, PyDictMethods};
+use crate::{envelope, Error, StackFrame};
+use crate::frame::MAX_CAUSE_DEPTH;
 
 import_exception!(teo, TeoException);
 
 impl From<PyErr> for Error {
     fn from(value: PyErr) -> Self {
-        let result: Result<Error, Error> = Python::with_gil(|py| {
-            if value.get_type(py).is(&PyType::new::<TeoException>(py)) {
-                let py_object: PyObject = value.clone_ref(py).into_py_any(py)?;
-                let code: u16 = py_object.getattr(py, "code")?.extract(py)?;
-                let message: String = py_object.getattr(py, "error_message")?.extract(py)?;
-                let errors_py = py_object.getattr(py, "errors")?;
-                let errors = if errors_py.is_none(py) {
-                    None
-                } else {
-                    let dict: Bound<PyDict> = errors_py.extract(py)?;
-                    let mut map_result: IndexMap<String, String> = IndexMap::new();
-                    for (k, v) in dict.iter() {
-                        let k_string: String = k.extract()?;
-                        let v_string: String = v.extract()?;
-                        map_result.insert(k_string, v_string);
-                    }
-                    Some(map_result)
-                };
-                let mut teo_error = if let Some(errors) = errors {
-                    Error::new_with_code_errors(message, code, errors)
-                } else {
-                    Error::new_with_code(message, code)
-                };
-                teo_error.assign_platform_native_object(value);
-                Err(teo_error)
+        Python::with_gil(|py| error_from_py_err(py, value, 0))
+    }
+}
+
+fn error_from_py_err(py: Python<'_>, value: PyErr, depth: usize) -> Error {
+    let frames = traceback_frames(py, &value);
+    let cause = if depth < MAX_CAUSE_DEPTH {
+        exception_cause(py, &value).map(|cause| error_from_py_err(py, cause, depth + 1))
+    } else {
+        None
+    };
+    let result: Result<Error, Error> = (|| {
+        // a `TeoException` raised with only an envelope string carries no attributes
+        if value.get_type(py).is(&PyType::new::<TeoException>(py)) && value.value(py).hasattr("code")? {
+            let py_object: PyObject = value.clone_ref(py).into_py_any(py)?;
+            let code: u16 = py_object.getattr(py, "code")?.extract(py)?;
+            let message: String = py_object.getattr(py, "error_message")?.extract(py)?;
+            let errors_py = py_object.getattr(py, "errors")?;
+            let errors = if errors_py.is_none(py) {
+                None
             } else {
-                let mut error = Error::new(value.to_string());
-                error.assign_platform_native_object(value);
-                Err(error)
-            }
-        });
-        result.unwrap_or_else(|e| e)
+                let dict: Bound<PyDict> = errors_py.extract(py)?;
+                let mut map_result: IndexMap<String, String> = IndexMap::new();
+                for (k, v) in dict.iter() {
+                    let k_string: String = k.extract()?;
+                    let v_string: String = v.extract()?;
+                    map_result.insert(k_string, v_string);
+                }
+                Some(map_result)
+            };
+            let mut teo_error = if let Some(errors) = errors {
+                Error::new_with_code_errors(message, code, errors)
+            } else {
+                Error::new_with_code(message, code)
+            };
+            teo_error.assign_platform_native_object(value);
+            Err(teo_error)
+        } else {
+            let message: String = value.value(py).str()?.extract()?;
+            let mut error = envelope::decode(message.as_str()).unwrap_or_else(|| Error::new(value.to_string()));
+            error.assign_platform_native_object(value);
+            Err(error)
+        }
+    })();
+    let mut error = result.unwrap_or_else(|e| e);
+    // frames decoded from an envelope come from the runtime which raised the error
+    if error.frames().is_empty() {
+        error.assign_frames(frames);
     }
+    if let Some(cause) = cause {
+        error.assign_cause(cause);
+    }
+    error
+}
+
+fn traceback_frames(py: Python<'_>, value: &PyErr) -> Vec<StackFrame> {
+    let mut frames = Vec::new();
+    let mut traceback: Option<Bound<PyAny>> = value.traceback(py).map(|traceback| traceback.into_any());
+    while let Some(current) = traceback {
+        let code = current.getattr("tb_frame").and_then(|frame| frame.getattr("f_code")).ok();
+        frames.push(StackFrame::new(
+            code.as_ref().and_then(|code| code.getattr("co_filename").and_then(|file| file.extract()).ok()),
+            current.getattr("tb_lineno").and_then(|line| line.extract()).ok(),
+            code.as_ref().and_then(|code| code.getattr("co_name").and_then(|name| name.extract()).ok()),
+        ));
+        traceback = current.getattr("tb_next").ok().filter(|next| !next.is_none());
+    }
+    // Python tracebacks start at the outermost call
+    frames.reverse();
+    frames
+}
+
+fn exception_cause(py: Python<'_>, value: &PyErr) -> Option<PyErr> {
+    let exception = value.value(py);
+    if let Some(cause) = exception.getattr("__cause__").ok().filter(|cause| !cause.is_none()) {
+        return Some(PyErr::from_value(cause));
+    }
+    let suppress_context: bool = exception.getattr("__suppress_context__").and_then(|suppress| suppress.extract()).unwrap_or(false);
+    if suppress_context {
+        return None;
+    }
+    exception.getattr("__context__").ok().filter(|context| !context.is_none()).map(PyErr::from_value)
 }
 
 impl From<Error> for PyErr {
-    fn from(value: Error) -> Self {
+    fn from(mut value: Error) -> Self {
+        let cause = value.take_cause();
         let result: Result<PyErr, PyErr> = Python::with_gil(|py| {
             let meta: Option<&PyErr> = value.platform_native_object();
             if let Some(err) = meta {
                 Err(PyErr::from_value(err.into_bound_py_any(py)?))
             } else {
-                let err = TeoException::new_err("");
+                let err = TeoException::new_err(envelope::encode(&value));
                 let py_object: PyObject = err.clone_ref(py).into_py_any(py)?;
                 py_object.setattr(py, "error_message", value.message())?;
                 py_object.setattr(py, "code", value.code)?;
@@ -62,9 +113,13 @@ impl From<Error> for PyErr {
                 } else {
                     py_object.setattr(py, "errors", ())?;
                 }
+                if let Some(cause) = cause {
+                    // mirrors `raise err from cause`
+                    err.set_cause(py, Some(PyErr::from(cause)));
+                }
                 Err(err)
             }
         });
         result.unwrap_or_else(|e| e)
     }
-}
\ No newline at end of file
+}
@@ -0,0 +1,26 @@
+use crate::{Error, ErrorSerializable, Result};
+
+impl From<rmp_serde::encode::Error> for Error {
+    fn from(value: rmp_serde::encode::Error) -> Self {
+        Error::new(format!("msgpack encode error: {}", value))
+    }
+}
+
+impl From<rmp_serde::decode::Error> for Error {
+    fn from(value: rmp_serde::decode::Error) -> Self {
+        Error::new(format!("msgpack decode error: {}", value))
+    }
+}
+
+impl Error {
+
+    pub fn to_msgpack(&self) -> Result<Vec<u8>> {
+        self.ensure_incident_id();
+        Ok(rmp_serde::to_vec_named(&ErrorSerializable::unredacted(self))?)
+    }
+
+    pub fn from_msgpack(bytes: &[u8]) -> Result<Self> {
+        let serializable: ErrorSerializable = rmp_serde::from_slice(bytes)?;
+        Ok(Error::from(serializable))
+    }
+}
@@ -0,0 +1,78 @@
+use indexmap::IndexMap;
+use js_sys::{Array, Object, Reflect, JSON};
+use wasm_bindgen::{JsCast, JsValue};
+use crate::{envelope, Error};
+use crate::frame::{parse_js_stack, MAX_CAUSE_DEPTH};
+
+impl From<Error> for JsValue {
+    fn from(mut value: Error) -> Self {
+        let cause = value.take_cause();
+        let error = js_sys::Error::new(value.message());
+        let _ = Reflect::set(&error, &JsValue::from_str("code"), &JsValue::from(value.code));
+        let errors = if let Some(errors) = value.errors {
+            let object = Object::new();
+            for (k, v) in errors {
+                let _ = Reflect::set(&object, &JsValue::from(k), &JsValue::from(v));
+            }
+            object.into()
+        } else {
+            JsValue::NULL
+        };
+        let _ = Reflect::set(&error, &JsValue::from_str("errors"), &errors);
+        if let Some(cause) = cause {
+            error.set_cause(&JsValue::from(cause));
+        }
+        error.into()
+    }
+}
+
+impl From<JsValue> for Error {
+    fn from(value: JsValue) -> Self {
+        error_from_js_value(&value, 0)
+    }
+}
+
+fn error_from_js_value(value: &JsValue, depth: usize) -> Error {
+    if !value.is_object() {
+        return match value.as_string() {
+            Some(message) => envelope::decode(message.as_str()).unwrap_or_else(|| Error::new(message)),
+            None => Error::new(js_string(value)),
+        };
+    }
+    let property = |key: &str| Reflect::get(value, &JsValue::from_str(key)).unwrap_or(JsValue::UNDEFINED);
+    let message = property("message").as_string();
+    // only objects shaped like those built by `From<Error> for JsValue`, an
+    // unrelated `code` such as Node's `ENOENT` or `-2` falls through
+    let code = property("code").as_f64().filter(|code| code.fract() == 0.0 && (0.0..=u16::MAX as f64).contains(code));
+    let has_errors = Reflect::has(value, &JsValue::from_str("errors")).unwrap_or(false);
+    let mut error = match (code.filter(|_| has_errors), message) {
+        (Some(code), Some(message)) => {
+            let errors = property("errors");
+            if let Some(errors) = errors.dyn_ref::<Object>() {
+                Error::new_with_code_errors(message, code as u16, Object::entries(errors).iter().filter_map(|entry| {
+                    let entry: Array = entry.unchecked_into();
+                    Some((entry.get(0).as_string()?, entry.get(1).as_string()?))
+                }).collect::<IndexMap<String, String>>())
+            } else {
+                Error::new_with_code(message, code as u16)
+            }
+        }
+        (_, Some(message)) => envelope::decode(message.as_str()).unwrap_or_else(|| Error::new(message)),
+        (_, None) => Error::new(js_string(value)),
+    };
+    // frames and cause decoded from an envelope take precedence
+    if error.frames().is_empty() {
+        if let Some(stack) = property("stack").as_string() {
+            error.assign_frames(parse_js_stack(stack.as_str()));
+        }
+    }
+    let cause = property("cause");
+    if error.cause().is_none() && depth < MAX_CAUSE_DEPTH && !cause.is_undefined() && !cause.is_null() {
+        error.assign_cause(error_from_js_value(&cause, depth + 1));
+    }
+    error
+}
+
+fn js_string(value: &JsValue) -> String {
+    JSON::stringify(value).ok().and_then(|string| string.as_string()).unwrap_or_else(|| "unknown JavaScript error".to_owned())
+}
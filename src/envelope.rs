@@ -0,0 +1,99 @@
+//! Versioned wire format for passing errors between runtimes.
+//!
+//! An envelope is the `TeoError: ` prefix followed by a JSON object carrying
+//! a `version` field. Strings written before the version field existed are
+//! decoded as version 0. Envelopes of versions newer than [`VERSION`] are
+//! rejected, since their fields may mean something else.
+//!
+//! Envelopes become messages of foreign exceptions, so [`encode`] leaves the
+//! internal message and annotations out and applies the global
+//! [`RedactionPolicy`](crate::redaction::RedactionPolicy) to the error and
+//! its causes. [`encode_internal`] keeps everything for bridges which never
+//! show the message to clients.
+
+use serde::{Deserialize, Serialize};
+use crate::Error;
+use crate::error::Serialized;
+
+pub const VERSION: u32 = 1;
+
+pub const PREFIX: &str = "TeoError: ";
+
+const MAGIC: &[u8; 3] = b"TEO";
+
+// the version byte of `encode_bytes`
+const VERSION_BYTE: u8 = {
+    assert!(VERSION <= u8::MAX as u32, "envelope version must fit into one byte");
+    VERSION as u8
+};
+
+#[derive(Serialize)]
+struct Envelope<'a> {
+    version: u32,
+    #[serde(flatten)]
+    error: Serialized<'a>,
+}
+
+#[derive(Deserialize)]
+struct OwnedEnvelope {
+    #[serde(default)]
+    version: u32,
+    #[serde(flatten)]
+    error: Error,
+}
+
+fn envelope(error: &Error, internal: bool) -> Envelope<'_> {
+    Envelope { version: VERSION, error: Serialized { error, internal } }
+}
+
+fn open(envelope: OwnedEnvelope) -> Option<Error> {
+    (envelope.version <= VERSION).then_some(envelope.error)
+}
+
+/// Encodes the public form of an error into a prefixed envelope string.
+pub fn encode(error: &Error) -> String {
+    format!("{}{}", PREFIX, serde_json::to_string(&envelope(error, false)).unwrap())
+}
+
+/// Like [`encode`], but keeps the internal message and annotations and
+/// ignores the redaction policy.
+pub fn encode_internal(error: &Error) -> String {
+    format!("{}{}", PREFIX, serde_json::to_string(&envelope(error, true)).unwrap())
+}
+
+/// Decodes an envelope string. A leading `Error: ` added by JavaScript's
+/// `toString` is accepted. Returns `None` if the input is not an envelope
+/// or has a newer version.
+pub fn decode(input: &str) -> Option<Error> {
+    let input = input.strip_prefix("Error: ").unwrap_or(input);
+    let json = input.strip_prefix(PREFIX)?;
+    serde_json::from_str::<OwnedEnvelope>(json).ok().and_then(open)
+}
+
+/// Returns `true` if the input looks like an envelope string, regardless of
+/// whether its body can be decoded.
+pub fn is_envelope(input: &str) -> bool {
+    input.strip_prefix("Error: ").unwrap_or(input).starts_with(PREFIX)
+}
+
+/// Encodes an error into bytes: a `TEO` magic, one version byte and the
+/// JSON body.
+pub fn encode_bytes(error: &Error) -> Vec<u8> {
+    let mut bytes = MAGIC.to_vec();
+    bytes.push(VERSION_BYTE);
+    serde_json::to_writer(&mut bytes, &envelope(error, false)).unwrap();
+    bytes
+}
+
+/// Decodes bytes produced by [`encode_bytes`]. UTF-8 envelope strings are
+/// accepted as well. Returns `None` for a newer version byte.
+pub fn decode_bytes(input: &[u8]) -> Option<Error> {
+    match input.strip_prefix(MAGIC) {
+        Some([version, json @ ..]) => if *version <= VERSION_BYTE {
+            serde_json::from_slice::<OwnedEnvelope>(json).ok().and_then(open)
+        } else {
+            None
+        },
+        _ => decode(std::str::from_utf8(input).ok()?),
+    }
+}
@@ -1,16 +1,192 @@
 use std::any::Any;
+use std::backtrace::{Backtrace, BacktraceStatus};
 use std::borrow::Cow;
 use std::fmt::{Debug, Display, Formatter};
-use std::sync::Arc;
+use std::sync::{Arc, OnceLock};
+use std::time::{Duration, SystemTime};
 use indexmap::{IndexMap, indexmap};
-use serde::{Deserialize, Serialize};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
 use serde_json::Value;
+use crate::context::{self, Context};
+use crate::extensions::Extensions;
+use crate::frame::StackFrame;
+use crate::incident::{self, generate_incident_id, format_timestamp};
+use crate::redaction::{self, RedactionPolicy};
 
+#[derive(Clone)]
 pub struct Error {
     pub code: u16,
     pub message: String,
     pub errors: Option<IndexMap<String, String>>,
     pub platform_native_object: Option<Arc<dyn Any + Send + Sync>>,
+    // allocated on first use, so that errors which only carry the fields
+    // above stay small
+    details: OnceLock<Box<Details>>,
+}
+
+/// Data which most errors never set.
+#[derive(Clone, Default)]
+struct Details {
+    internal_message: Option<String>,
+    frames: Vec<StackFrame>,
+    backtrace: Option<Arc<Backtrace>>,
+    cause: Option<Error>,
+    incident_id: OnceLock<String>,
+    created_at: Option<SystemTime>,
+    // shared with the scope the error was created in until annotated
+    annotations: Option<Arc<Context>>,
+    contexts: Vec<String>,
+    extensions: Extensions,
+    retry_after: Option<Duration>,
+    headers: IndexMap<String, String>,
+}
+
+/// The serialized form of [`Error`].
+#[derive(Serialize)]
+struct SerializedError<'a> {
+    code: u16,
+    message: &'a str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    internal_message: Option<&'a str>,
+    errors: Option<&'a IndexMap<String, String>>,
+    #[serde(skip_serializing_if = "<[_]>::is_empty")]
+    frames: &'a [StackFrame],
+    #[serde(skip_serializing_if = "Option::is_none")]
+    cause: Option<Serialized<'a>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    incident_id: Option<&'a str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    created_at: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    annotations: Option<&'a IndexMap<String, String>>,
+    #[serde(skip_serializing_if = "<[_]>::is_empty")]
+    contexts: &'a [String],
+    #[serde(skip_serializing_if = "Option::is_none")]
+    retry_after: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    headers: Option<&'a IndexMap<String, String>>,
+}
+
+#[derive(Deserialize)]
+struct DeserializedError {
+    code: u16,
+    message: String,
+    #[serde(default)]
+    internal_message: Option<String>,
+    #[serde(default)]
+    errors: Option<IndexMap<String, String>>,
+    #[serde(default)]
+    frames: Vec<StackFrame>,
+    #[serde(default)]
+    cause: Option<Box<Error>>,
+    #[serde(default)]
+    incident_id: Option<String>,
+    #[serde(default)]
+    created_at: Option<String>,
+    #[serde(default)]
+    annotations: IndexMap<String, String>,
+    #[serde(default)]
+    contexts: Vec<String>,
+    #[serde(default)]
+    retry_after: Option<u64>,
+    #[serde(default)]
+    headers: IndexMap<String, String>,
+}
+
+/// Serializes an error. Unless `internal` is set, the internal message and
+/// annotations are left out and the global redaction policy is applied to
+/// the error and its causes, since envelopes end up as messages of foreign
+/// exceptions.
+pub(crate) struct Serialized<'a> {
+    pub(crate) error: &'a Error,
+    pub(crate) internal: bool,
+}
+
+impl Serialize for Serialized<'_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        if self.internal {
+            return self.error.serialized(true).serialize(serializer);
+        }
+        match redaction::global_policy().redacted(self.error) {
+            Some(view) => view.serialized(false).serialize(serializer),
+            None => self.error.serialized(false).serialize(serializer),
+        }
+    }
+}
+
+impl Error {
+
+    fn serialized(&self, internal: bool) -> SerializedError<'_> {
+        let details = self.details();
+        SerializedError {
+            code: self.code,
+            message: &self.message,
+            internal_message: self.internal_message().filter(|_| internal),
+            errors: self.errors.as_ref(),
+            frames: self.frames(),
+            cause: self.cause().map(|cause| Serialized { error: cause, internal }),
+            incident_id: self.generated_incident_id(),
+            created_at: self.created_at().map(format_timestamp),
+            annotations: details.and_then(|details| details.annotations.as_deref()).filter(|annotations| internal && !annotations.is_empty()),
+            contexts: self.contexts(),
+            retry_after: self.retry_after_secs(),
+            headers: details.map(|details| &details.headers).filter(|headers| !headers.is_empty()),
+        }
+    }
+}
+
+/// Serializes the public form sent to clients, the same as
+/// [`ErrorSerializable::from_error`]: redacted by the global policy and
+/// without the internal message, annotations, frames or cause. Wrap the
+/// error in [`Lossless`] to keep every field.
+impl Serialize for Error {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        ErrorSerializable::from_error(self).serialize(serializer)
+    }
+}
+
+/// Serializes every field of an error, including the internal message,
+/// annotations, frames and cause, without applying the redaction policy.
+/// Meant for storage and trusted peers. Deserializing [`Error`] accepts both
+/// forms.
+pub struct Lossless<'a>(pub &'a Error);
+
+impl Serialize for Lossless<'_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.0.serialized(true).serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for Error {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let value = DeserializedError::deserialize(deserializer)?;
+        // annotated with the active scope like any other new error, values
+        // of the decoded annotations win
+        let mut error = Error::decoded(value.message, value.code);
+        error.errors = value.errors;
+        let has_details = value.internal_message.is_some() || !value.frames.is_empty() || value.cause.is_some()
+            || value.incident_id.is_some() || value.created_at.is_some() || !value.annotations.is_empty()
+            || !value.contexts.is_empty() || value.retry_after.is_some() || !value.headers.is_empty();
+        if has_details {
+            let details = error.details_mut();
+            details.internal_message = value.internal_message;
+            details.frames = value.frames;
+            details.cause = value.cause.map(|cause| *cause);
+            if let Some(incident_id) = value.incident_id {
+                details.incident_id = OnceLock::from(incident_id);
+            }
+            if let Some(created_at) = value.created_at.as_deref().and_then(incident::parse_timestamp) {
+                details.created_at = Some(created_at);
+            }
+            if !value.annotations.is_empty() {
+                Arc::make_mut(details.annotations.get_or_insert_with(Default::default)).extend(value.annotations);
+            }
+            details.contexts = value.contexts;
+            details.retry_after = value.retry_after.map(Duration::from_secs);
+            details.headers = value.headers.into_iter().map(|(name, value)| (name.to_ascii_lowercase(), value)).collect();
+        }
+        Ok(error)
+    }
 }
 
 #[derive(Serialize, Deserialize)]
@@ -18,10 +194,29 @@ pub struct ErrorSerializable {
     pub code: u16,
     pub message: String,
     pub errors: Value,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub incident_id: Option<String>,
+    /// RFC 3339 creation time, see [`incident::format_timestamp`].
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub created_at: Option<String>,
+    /// Seconds to wait before retrying.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub retry_after: Option<u64>,
 }
 
 impl ErrorSerializable {
+
+    /// Builds the public form of an error, redacted by the global policy.
     pub fn from_error(error: &Error) -> Self {
+        Self::from_error_with_policy(error, &redaction::global_policy())
+    }
+
+    pub fn from_error_with_policy(error: &Error, policy: &RedactionPolicy) -> Self {
+        error.ensure_incident_id();
+        Self::unredacted(&policy.apply(error))
+    }
+
+    pub(crate) fn unredacted(error: &Error) -> Self {
         ErrorSerializable {
             code: error.code,
             message: error.message().to_string(),
@@ -30,6 +225,9 @@ impl ErrorSerializable {
             } else {
                 Value::Null
             },
+            incident_id: error.generated_incident_id().map(ToOwned::to_owned),
+            created_at: error.created_at().map(format_timestamp),
+            retry_after: error.retry_after_secs(),
         }
     }
 
@@ -39,97 +237,126 @@ impl ErrorSerializable {
     }
 }
 
+impl From<ErrorSerializable> for Error {
+    fn from(value: ErrorSerializable) -> Self {
+        let errors = value.errors.as_object().map(|object| object.iter().map(|(k, v)| {
+            (k.to_string(), v.as_str().map(ToOwned::to_owned).unwrap_or_else(|| v.to_string()))
+        }).collect());
+        let mut error = Error::decoded(value.message, value.code);
+        error.errors = errors;
+        if let Some(incident_id) = value.incident_id {
+            error.assign_incident_id(incident_id);
+        }
+        if let Some(created_at) = value.created_at.as_deref().and_then(incident::parse_timestamp) {
+            error.assign_created_at(created_at);
+        }
+        if let Some(retry_after) = value.retry_after {
+            error.assign_retry_after(Duration::from_secs(retry_after));
+        }
+        error
+    }
+}
+
 impl Error {
 
     pub fn new(message: impl Into<String>) -> Self {
-        Self {
-            code: 500,
-            message: message.into(),
-            errors: None,
-            platform_native_object: None,
-        }
+        Self::new_with_code(message, 500)
     }
 
     pub fn new_with_code(message: impl Into<String>, code: u16) -> Self {
-        Self {
+        let mut error = Self::decoded(message, code);
+        if code >= 500 {
+            let backtrace = Backtrace::capture();
+            if backtrace.status() == BacktraceStatus::Captured {
+                error.details_mut().backtrace = Some(Arc::new(backtrace));
+            }
+        }
+        error
+    }
+
+    /// Like [`Error::new_with_code`], but without a backtrace, which would
+    /// only point into the decoder.
+    pub(crate) fn decoded(message: impl Into<String>, code: u16) -> Self {
+        let mut error = Self {
             code,
             message: message.into(),
             errors: None,
             platform_native_object: None,
+            details: OnceLock::new(),
+        };
+        if incident::mode().covers(code) {
+            error.details_mut().created_at = Some(SystemTime::now());
         }
+        // generated up front so that clones share it
+        error.ensure_incident_id();
+        if let Some(context) = context::current() {
+            error.details_mut().annotations = Some(context);
+        }
+        error
     }
 
     pub fn new_with_code_errors(message: impl Into<String>, code: u16, errors: IndexMap<String, String>) -> Self {
         Self {
-            code,
-            message: message.into(),
             errors: Some(errors),
-            platform_native_object: None,
+            ..Self::new_with_code(message, code)
         }
     }
 
+    pub fn new_with_internal_message(message: impl Into<String>, code: u16, internal_message: impl Into<String>) -> Self {
+        Self::new_with_code(message, code).with_internal_message(internal_message)
+    }
+
     pub fn new_pathed(message: impl Into<String>, code: u16, key: impl Into<String>, value: impl Into<String>) -> Self {
-        Self {
-            code,
-            message: message.into(),
-            errors: Some(indexmap! { key.into() => value.into() }),
-            platform_native_object: None,
+        Self::new_with_code_errors(message, code, indexmap! { key.into() => value.into() })
+    }
+
+    fn details(&self) -> Option<&Details> {
+        self.details.get().map(|details| &**details)
+    }
+
+    fn details_mut(&mut self) -> &mut Details {
+        if self.details.get().is_none() {
+            let _ = self.details.set(Box::default());
         }
+        self.details.get_mut().unwrap()
     }
 
+    /// Prepends `prefix` to the message and every path error. Prefer
+    /// [`Error::context`], which leaves them untouched.
     pub fn message_prefixed(&self, prefix: impl AsRef<str>) -> Self {
         Self {
-            code: self.code,
             message: if self.errors.is_some() {
                 self.message.clone()
             } else {
                 format!("{}: {}", prefix.as_ref(), self.message())
             },
-            errors: if let Some(errors) = self.errors.as_ref() {
-                Some(errors.iter().map(|(k, v)| (k.clone(), format!("{}: {}", prefix.as_ref(), v))).collect())
-            } else {
-                None
-            },
-            platform_native_object: self.platform_native_object.clone(),
+            errors: self.errors.as_ref().map(|errors| errors.iter().map(|(k, v)| (k.clone(), format!("{}: {}", prefix.as_ref(), v))).collect()),
+            ..self.clone()
         }
     }
 
     pub fn path_prefixed(&self, prefix: impl AsRef<str>) -> Self {
         Self {
-            code: self.code,
-            message: self.message.clone(),
-            errors: if let Some(errors) = self.errors.as_ref() {
-                Some(errors.iter().map(|(k, v)| (format!("{}.{}", prefix.as_ref(), k), v.clone())).collect())
-            } else {
-                None
-            },
-            platform_native_object: self.platform_native_object.clone(),
+            errors: self.errors.as_ref().map(|errors| errors.iter().map(|(k, v)| (format!("{}.{}", prefix.as_ref(), k), v.clone())).collect()),
+            ..self.clone()
         }
     }
 
     pub fn pathed(&self, prefix: impl AsRef<str>) -> Self {
         Self {
-            code: self.code,
-            message: self.message.clone(),
             errors: if let Some(errors) = self.errors.as_ref() {
                 Some(errors.iter().map(|(k, v)| (k.to_owned(), v.clone())).collect())
             } else {
                 Some(indexmap! {prefix.as_ref().to_string() => self.message.clone()})
             },
-            platform_native_object: self.platform_native_object.clone(),
+            ..self.clone()
         }
     }
 
     pub fn map_path<F>(&self, mapper: F) -> Self where F: Fn(&str) -> String {
         Self {
-            code: self.code,
-            message: self.message.clone(),
-            errors: if let Some(errors) = self.errors.as_ref() {
-                Some(errors.iter().map(|(k, v)| (mapper(k.as_str()), v.clone())).collect())
-            } else {
-                None
-            },
-            platform_native_object: self.platform_native_object.clone(),
+            errors: self.errors.as_ref().map(|errors| errors.iter().map(|(k, v)| (mapper(k.as_str()), v.clone())).collect()),
+            ..self.clone()
         }
     }
 
@@ -145,220 +372,435 @@ impl Error {
         self.errors.as_ref()
     }
 
+    /// Detail for logs which is never shown to clients.
+    pub fn internal_message(&self) -> Option<&str> {
+        self.details().and_then(|details| details.internal_message.as_deref())
+    }
+
+    pub fn with_internal_message(mut self, internal_message: impl Into<String>) -> Self {
+        self.assign_internal_message(internal_message);
+        self
+    }
+
+    pub fn assign_internal_message(&mut self, internal_message: impl Into<String>) {
+        self.details_mut().internal_message = Some(internal_message.into());
+    }
+
     pub fn assign_platform_native_object<T: 'static + Send + Sync>(&mut self, val: T) {
         self.platform_native_object = Some(Arc::new(val));
     }
 
     pub fn platform_native_object<T: 'static + Send>(&self) -> Option<&T> {
-        self.platform_native_object.as_ref().map(|boxed| boxed.downcast_ref()).flatten()
+        self.platform_native_object.as_ref().and_then(|boxed| boxed.downcast_ref())
     }
 
-    pub fn inferred_title(&self) -> Cow<'static, str> {
-        match self.code {
-            100 => Cow::Borrowed("Continue"),
-            101 => Cow::Borrowed("SwitchingProtocols"),
-            102 => Cow::Borrowed("Processing"),
-            103 => Cow::Borrowed("EarlyHints"),
-            200 => Cow::Borrowed("OK"),
-            201 => Cow::Borrowed("Created"),
-            202 => Cow::Borrowed("Accepted"),
-            203 => Cow::Borrowed("NonAuthoritativeInformation"),
-            204 => Cow::Borrowed("NoContent"),
-            205 => Cow::Borrowed("ResetContent"),
-            206 => Cow::Borrowed("PartialContent"),
-            207 => Cow::Borrowed("MultiStatus"),
-            208 => Cow::Borrowed("AlreadyReported"),
-            226 => Cow::Borrowed("IMUsed"),
-            300 => Cow::Borrowed("MultipleChoices"),
-            301 => Cow::Borrowed("MovedPermanently"),
-            302 => Cow::Borrowed("Found"),
-            303 => Cow::Borrowed("SeeOther"),
-            304 => Cow::Borrowed("NotModified"),
-            307 => Cow::Borrowed("TemporaryRedirect"),
-            308 => Cow::Borrowed("PermanentRedirect"),
-            400 => Cow::Borrowed("BadRequest"),
-            401 => Cow::Borrowed("Unauthorized"),
-            402 => Cow::Borrowed("PaymentRequired"),
-            403 => Cow::Borrowed("Forbidden"),
-            404 => Cow::Borrowed("NotFound"),
-            405 => Cow::Borrowed("MethodNotAllowed"),
-            406 => Cow::Borrowed("NotAcceptable"),
-            407 => Cow::Borrowed("ProxyAuthenticationRequired"),
-            408 => Cow::Borrowed("RequestTimeout"),
-            409 => Cow::Borrowed("Conflict"),
-            410 => Cow::Borrowed("Gone"),
-            411 => Cow::Borrowed("LengthRequired"),
-            412 => Cow::Borrowed("PreconditionFailed"),
-            413 => Cow::Borrowed("PayloadTooLarge"),
-            414 => Cow::Borrowed("URITooLong"),
-            415 => Cow::Borrowed("UnsupportedMediaType"),
-            416 => Cow::Borrowed("RangeNotSatisfiable"),
-            417 => Cow::Borrowed("ExpectationFailed"),
-            418 => Cow::Borrowed("ImATeapot"),
-            421 => Cow::Borrowed("MisdirectedRequest"),
-            422 => Cow::Borrowed("UnprocessableContent"),
-            423 => Cow::Borrowed("Locked"),
-            424 => Cow::Borrowed("FailedDependency"),
-            425 => Cow::Borrowed("TooEarly"),
-            426 => Cow::Borrowed("UpgradeRequired"),
-            428 => Cow::Borrowed("PreconditionRequired"),
-            429 => Cow::Borrowed("TooManyRequests"),
-            431 => Cow::Borrowed("RequestHeaderFieldsTooLarge"),
-            451 => Cow::Borrowed("UnavailableForLegalReasons"),
-            500 => Cow::Borrowed("InternalServerError"),
-            501 => Cow::Borrowed("NotImplemented"),
-            502 => Cow::Borrowed("BadGateway"),
-            503 => Cow::Borrowed("ServiceUnavailable"),
-            504 => Cow::Borrowed("GatewayTimeout"),
-            505 => Cow::Borrowed("HTTPVersionNotSupported"),
-            506 => Cow::Borrowed("VariantAlsoNegotiates"),
-            507 => Cow::Borrowed("InsufficientStorage"),
-            508 => Cow::Borrowed("LoopDetected"),
-            510 => Cow::Borrowed("NotExtended"),
-            511 => Cow::Borrowed("NetworkAuthenticationRequired"),
-            _ => Cow::Owned(format!("ServerError({})", self.code)),
-        }
-    }
-
-    pub fn not_found() -> Self {
-        Self {
-            code: 404,
-            message: "not found".to_owned(),
-            errors: None,
-            platform_native_object: None,
-        }
+    pub fn is_client_error(&self) -> bool {
+        (400..=499).contains(&self.code)
     }
 
-    pub fn not_found_message(message: impl Into<String>) -> Self {
-        Self {
-            code: 404,
-            message: message.into(),
-            errors: None,
-            platform_native_object: None,
-        }
+    pub fn is_server_error(&self) -> bool {
+        (500..=599).contains(&self.code)
     }
 
-    pub fn not_found_pathed(path: impl Into<String>, message: impl Into<String>) -> Self {
-        Self {
-            code: 404,
-            message: "not found".to_owned(),
-            errors: Some(indexmap! {
-                path.into() => message.into()
-            }),
-            platform_native_object: None,
-        }
+    /// Returns `true` if the same request may succeed later: a retry-after
+    /// hint is present, or the code is 408, 425, 429, 502, 503 or 504.
+    pub fn is_retryable(&self) -> bool {
+        self.retry_after().is_some() || matches!(self.code, 408 | 425 | 429 | 502 | 503 | 504)
     }
 
-    pub fn invalid_request() -> Self {
-        Self {
-            code: 400,
-            message: "value is invalid".to_owned(),
-            errors: None,
-            platform_native_object: None,
-        }
+    pub fn retry_after(&self) -> Option<Duration> {
+        self.details().and_then(|details| details.retry_after)
     }
 
-    pub fn invalid_request_message(message: impl Into<String>) -> Self {
-        Self {
-            code: 400,
-            message: message.into(),
-            errors: None,
-            platform_native_object: None,
-        }
+    /// The retry-after hint in whole seconds, rounded up so that a
+    /// sub-second hint doesn't become an immediate retry.
+    pub(crate) fn retry_after_secs(&self) -> Option<u64> {
+        self.retry_after().map(|duration| duration.as_secs().saturating_add(u64::from(duration.subsec_nanos() > 0)))
     }
 
-    pub fn invalid_request_pathed(path: impl Into<String>, message: impl Into<String>) -> Self {
-        Self {
-            code: 400,
-            message: "value is invalid".to_owned(),
-            errors: Some(indexmap! {
-                path.into() => message.into()
-            }),
-            platform_native_object: None,
+    pub fn with_retry_after(mut self, retry_after: Duration) -> Self {
+        self.assign_retry_after(retry_after);
+        self
+    }
+
+    pub fn assign_retry_after(&mut self, retry_after: Duration) {
+        self.details_mut().retry_after = Some(retry_after);
+    }
+
+    /// Response headers sent along with the error, e.g. `WWW-Authenticate`.
+    /// Names are stored in lowercase, as HTTP header names are
+    /// case-insensitive.
+    pub fn headers(&self) -> &IndexMap<String, String> {
+        self.details().map_or(empty_map(), |details| &details.headers)
+    }
+
+    pub fn with_header(mut self, name: impl Into<String>, value: impl Into<String>) -> Self {
+        self.insert_header(name, value);
+        self
+    }
+
+    pub fn insert_header(&mut self, name: impl Into<String>, value: impl Into<String>) {
+        self.details_mut().headers.insert(name.into().to_ascii_lowercase(), value.into());
+    }
+
+    /// Typed values attached to the error. Unlike the platform native
+    /// object, values of different types live side by side.
+    pub fn extensions(&self) -> &Extensions {
+        static EMPTY: Extensions = Extensions::new();
+        self.details().map_or(&EMPTY, |details| &details.extensions)
+    }
+
+    pub fn extensions_mut(&mut self) -> &mut Extensions {
+        &mut self.details_mut().extensions
+    }
+
+    /// Every failed attempt if the error was returned by
+    /// [`retry`](crate::retry::retry).
+    pub(crate) fn attempts(&self) -> &[Error] {
+        #[cfg(feature = "tokio")]
+        if let Some(attempts) = self.extensions().get::<crate::retry::Attempts>() {
+            return &attempts.0;
         }
+        &[]
     }
 
-    pub fn unique_error(path: impl Into<String>, constraint: impl AsRef<str>) -> Self {
-        Self {
-            code: 400,
-            message: "value is invalid".to_owned(),
-            errors: Some(indexmap! {
-                path.into() => format!("value violates '{}' constraint", constraint.as_ref())
-            }),
-            platform_native_object: None,
+    pub fn frames(&self) -> &[StackFrame] {
+        self.details().map_or(&[], |details| details.frames.as_slice())
+    }
+
+    pub fn assign_frames(&mut self, frames: Vec<StackFrame>) {
+        self.details_mut().frames = frames;
+    }
+
+    /// The Rust backtrace captured when a 5xx error is constructed, if
+    /// `RUST_BACKTRACE` or `RUST_LIB_BACKTRACE` enables it. Client errors
+    /// and decoded errors don't capture one. It is not serialized.
+    pub fn backtrace(&self) -> Option<&Backtrace> {
+        self.details().and_then(|details| details.backtrace.as_deref())
+    }
+
+    pub fn cause(&self) -> Option<&Error> {
+        self.details().and_then(|details| details.cause.as_ref())
+    }
+
+    pub fn assign_cause(&mut self, cause: Error) {
+        self.details_mut().cause = Some(cause);
+    }
+
+    /// Takes the cause out of the error.
+    pub fn take_cause(&mut self) -> Option<Error> {
+        self.details.get_mut().and_then(|details| details.cause.take())
+    }
+
+    /// Returns the incident ID of the error, generating it on first access.
+    /// Errors covered by the incident mode or redacted by the global policy
+    /// have it from construction on, so their clones share it. Clones of
+    /// other errors made before the first access get IDs of their own.
+    pub fn incident_id(&self) -> &str {
+        let details = self.details.get_or_init(Box::default);
+        details.incident_id.get_or_init(|| generate_incident_id(details.created_at))
+    }
+
+    /// Returns the incident ID only if it has been generated or assigned.
+    pub fn generated_incident_id(&self) -> Option<&str> {
+        self.details().and_then(|details| details.incident_id.get()).map(String::as_str)
+    }
+
+    pub fn assign_incident_id(&mut self, incident_id: impl Into<String>) {
+        self.details_mut().incident_id = OnceLock::from(incident_id.into());
+    }
+
+    /// Key-values of the context scope the error was created in, see
+    /// [`context`].
+    pub fn annotations(&self) -> &IndexMap<String, String> {
+        self.details().and_then(|details| details.annotations.as_deref()).unwrap_or(empty_map())
+    }
+
+    pub fn annotate(&mut self, key: impl Into<String>, value: impl Into<String>) {
+        Arc::make_mut(self.details_mut().annotations.get_or_insert_with(Default::default)).insert(key.into(), value.into());
+    }
+
+    /// Adds a breadcrumb describing what was being done when the error
+    /// occurred, e.g. `"loading user"`.
+    pub fn context(mut self, context: impl Into<String>) -> Self {
+        self.details_mut().contexts.push(context.into());
+        self
+    }
+
+    /// Breadcrumbs in the order they were added, innermost first.
+    pub fn contexts(&self) -> &[String] {
+        self.details().map_or(&[], |details| details.contexts.as_slice())
+    }
+
+    /// Renders the breadcrumbs and the message as a chain, outermost first,
+    /// e.g. `loading user: fetching row: not found`.
+    pub fn context_chain(&self) -> String {
+        self.contexts().iter().rev().map(String::as_str).chain(std::iter::once(self.message())).collect::<Vec<_>>().join(": ")
+    }
+
+    /// Returns the creation time, recorded only if the incident mode covered
+    /// the error when it was constructed.
+    pub fn created_at(&self) -> Option<SystemTime> {
+        self.details().and_then(|details| details.created_at)
+    }
+
+    pub fn assign_created_at(&mut self, created_at: SystemTime) {
+        self.details_mut().created_at = Some(created_at);
+    }
+
+    /// A copy with only the fields shown to clients, which leaves everything
+    /// else behind instead of cloning it.
+    pub(crate) fn public_view(&self, message: String, errors: Option<IndexMap<String, String>>) -> Error {
+        let details = OnceLock::new();
+        if let Some(source) = self.details() {
+            let _ = details.set(Box::new(Details {
+                incident_id: source.incident_id.clone(),
+                created_at: source.created_at,
+                retry_after: source.retry_after,
+                headers: source.headers.clone(),
+                ..Details::default()
+            }));
         }
+        Error { code: self.code, message, errors, platform_native_object: None, details }
     }
 
-    pub fn internal_server_error() -> Self {
-        Self {
-            code: 500,
-            message: "internal server error".to_owned(),
-            errors: None,
-            platform_native_object: None,
+    /// Generates the incident ID if the incident mode or the redaction
+    /// policy asks for one, so that logs and clients see the same ID.
+    pub(crate) fn ensure_incident_id(&self) {
+        if incident::mode().covers(self.code) || redaction::globally_redacts(self) {
+            self.incident_id();
         }
     }
 
-    pub fn internal_server_error_message(message: impl Into<String>) -> Self {
-        Self {
-            code: 500,
-            message: message.into(),
-            errors: None,
-            platform_native_object: None,
+    pub fn inferred_title(&self) -> Cow<'static, str> {
+        match self.status_title() {
+            Some(title) => Cow::Borrowed(title),
+            None => Cow::Owned(format!("ServerError({})", self.code)),
         }
     }
 
-    pub fn internal_server_error_pathed(path: impl Into<String>, message: impl Into<String>) -> Self {
-        Self {
-            code: 500,
-            message: "internal server error".to_owned(),
-            errors: Some(indexmap! {
-                path.into() => message.into()
-            }),
-            platform_native_object: None,
+    /// The title of a registered status code, e.g. `NotFound` for 404.
+    pub(crate) fn status_title(&self) -> Option<&'static str> {
+        match self.code {
+            100 => Some("Continue"),
+            101 => Some("SwitchingProtocols"),
+            102 => Some("Processing"),
+            103 => Some("EarlyHints"),
+            200 => Some("OK"),
+            201 => Some("Created"),
+            202 => Some("Accepted"),
+            203 => Some("NonAuthoritativeInformation"),
+            204 => Some("NoContent"),
+            205 => Some("ResetContent"),
+            206 => Some("PartialContent"),
+            207 => Some("MultiStatus"),
+            208 => Some("AlreadyReported"),
+            226 => Some("IMUsed"),
+            300 => Some("MultipleChoices"),
+            301 => Some("MovedPermanently"),
+            302 => Some("Found"),
+            303 => Some("SeeOther"),
+            304 => Some("NotModified"),
+            307 => Some("TemporaryRedirect"),
+            308 => Some("PermanentRedirect"),
+            400 => Some("BadRequest"),
+            401 => Some("Unauthorized"),
+            402 => Some("PaymentRequired"),
+            403 => Some("Forbidden"),
+            404 => Some("NotFound"),
+            405 => Some("MethodNotAllowed"),
+            406 => Some("NotAcceptable"),
+            407 => Some("ProxyAuthenticationRequired"),
+            408 => Some("RequestTimeout"),
+            409 => Some("Conflict"),
+            410 => Some("Gone"),
+            411 => Some("LengthRequired"),
+            412 => Some("PreconditionFailed"),
+            413 => Some("PayloadTooLarge"),
+            414 => Some("URITooLong"),
+            415 => Some("UnsupportedMediaType"),
+            416 => Some("RangeNotSatisfiable"),
+            417 => Some("ExpectationFailed"),
+            418 => Some("ImATeapot"),
+            421 => Some("MisdirectedRequest"),
+            422 => Some("UnprocessableContent"),
+            423 => Some("Locked"),
+            424 => Some("FailedDependency"),
+            425 => Some("TooEarly"),
+            426 => Some("UpgradeRequired"),
+            428 => Some("PreconditionRequired"),
+            429 => Some("TooManyRequests"),
+            431 => Some("RequestHeaderFieldsTooLarge"),
+            451 => Some("UnavailableForLegalReasons"),
+            500 => Some("InternalServerError"),
+            501 => Some("NotImplemented"),
+            502 => Some("BadGateway"),
+            503 => Some("ServiceUnavailable"),
+            504 => Some("GatewayTimeout"),
+            505 => Some("HTTPVersionNotSupported"),
+            506 => Some("VariantAlsoNegotiates"),
+            507 => Some("InsufficientStorage"),
+            508 => Some("LoopDetected"),
+            510 => Some("NotExtended"),
+            511 => Some("NetworkAuthenticationRequired"),
+            _ => None,
         }
     }
 
-    pub fn unauthorized() -> Self {
-        Self {
-            code: 401,
-            message: "unauthorized".to_owned(),
-            errors: None,
-            platform_native_object: None,
+    /// Splits the inferred title into words, e.g. `HTTPVersionNotSupported`
+    /// into `HTTP`, `Version`, `Not` and `Supported`.
+    pub(crate) fn inferred_title_words(&self) -> Vec<String> {
+        let title: Vec<char> = self.inferred_title().chars().collect();
+        let mut words: Vec<String> = Vec::new();
+        for (index, char) in title.iter().enumerate() {
+            let boundary = index > 0 && char.is_ascii_uppercase() && (title[index - 1].is_ascii_lowercase() || title.get(index + 1).is_some_and(|next| next.is_ascii_lowercase()));
+            match words.last_mut() {
+                Some(word) if !boundary => word.push(*char),
+                _ => words.push(char.to_string()),
+            }
         }
+        words
     }
 
-    pub fn unauthorized_message(message: impl Into<String>) -> Self {
-        Self {
-            code: 401,
-            message: message.into(),
-            errors: None,
-            platform_native_object: None,
+    /// The inferred title as words, e.g. `Not Found`. Codes without a
+    /// registered title are named by their class, e.g. `Server Error` for
+    /// 599, or just `Error` outside of 4xx and 5xx.
+    pub(crate) fn title_text(&self) -> String {
+        match self.status_title() {
+            Some(_) => self.inferred_title_words().join(" "),
+            None => match self.code {
+                400..=499 => "Client Error",
+                500..=599 => "Server Error",
+                _ => "Error",
+            }.to_owned(),
         }
     }
 
-    pub fn unauthorized_pathed(path: impl Into<String>, message: impl Into<String>) -> Self {
-        Self {
-            code: 401,
-            message: "unauthorized".to_owned(),
-            errors: Some(indexmap! {
-                path.into() => message.into()
-            }),
-            platform_native_object: None,
+    pub fn unique_error(path: impl Into<String>, constraint: impl AsRef<str>) -> Self {
+        Self::new_pathed("value is invalid", 400, path, format!("value violates '{}' constraint", constraint.as_ref()))
+    }
+
+    /// Builds a 401 with a `WWW-Authenticate` challenge, e.g.
+    /// `Bearer realm="api"`.
+    pub fn unauthorized_with_challenge(scheme: impl AsRef<str>, realm: impl AsRef<str>) -> Self {
+        let realm = realm.as_ref().replace('\\', "\\\\").replace('"', "\\\"");
+        Self::unauthorized().with_header("WWW-Authenticate", format!("{} realm=\"{}\"", scheme.as_ref(), realm))
+    }
+
+    /// Builds a 405 with an `Allow` header listing the allowed methods.
+    pub fn method_not_allowed<I, S>(allowed: I) -> Self where I: IntoIterator<Item = S>, S: AsRef<str> {
+        let allowed: Vec<String> = allowed.into_iter().map(|method| method.as_ref().to_owned()).collect();
+        Self::new_with_code("method not allowed", 405).with_header("Allow", allowed.join(", "))
+    }
+
+    pub fn too_many_requests(retry_after: impl Into<Option<Duration>>) -> Self {
+        Self::new_with_code("too many requests", 429).with_optional_retry_after(retry_after.into())
+    }
+
+    pub fn service_unavailable(retry_after: impl Into<Option<Duration>>) -> Self {
+        Self::new_with_code("service unavailable", 503).with_optional_retry_after(retry_after.into())
+    }
+
+    fn with_optional_retry_after(self, retry_after: Option<Duration>) -> Self {
+        match retry_after {
+            Some(retry_after) => self.with_retry_after(retry_after),
+            None => self,
         }
     }
 }
 
 impl Debug for Error {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        let serialized = ErrorSerializable::error_string(self);
-        f.write_str(&format!("teo_result::Error: {}", serialized))
+        // make sure logs carry the incident ID clients are shown
+        self.ensure_incident_id();
+        let serialized = serde_json::to_string(&ErrorSerializable::unredacted(self)).unwrap();
+        f.write_str(&format!("teo_result::Error: {}", serialized))?;
+        if let Some(internal_message) = self.internal_message() {
+            write!(f, "\n    internal: {}", internal_message)?;
+        }
+        if !self.annotations().is_empty() {
+            let annotations: Vec<String> = self.annotations().iter().map(|(k, v)| format!("{}={}", k, v)).collect();
+            write!(f, "\n    context: {}", annotations.join(", "))?;
+        }
+        for context in self.contexts().iter().rev() {
+            write!(f, "\n    while {}", context)?;
+        }
+        for frame in self.frames() {
+            write!(f, "\n    at {}", frame)?;
+        }
+        for (index, attempt) in self.attempts().iter().enumerate() {
+            write!(f, "\n    attempt {}: {} {}", index + 1, attempt.code, attempt.message())?;
+        }
+        if let Some(cause) = self.cause() {
+            write!(f, "\nCaused by: {:?}", cause)?;
+        }
+        Ok(())
     }
 }
 
+/// Renders `400 BadRequest: value is invalid (user.email: invalid format)`.
+/// The alternate form `{:#}` renders a multi-line tree which includes path
+/// errors, the incident ID if one was generated or the incident mode covers
+/// the code, stack frames, failed retry attempts, the captured backtrace and
+/// the cause chain.
 impl Display for Error {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        Debug::fmt(self, f)
+        write!(f, "{} {}: {}", self.code, self.inferred_title(), self.message)?;
+        if f.alternate() {
+            self.fmt_tree(f)
+        } else {
+            if let Some(errors) = self.errors.as_ref().filter(|errors| !errors.is_empty()) {
+                let errors: Vec<String> = errors.iter().map(|(k, v)| format!("{}: {}", k, v)).collect();
+                write!(f, " ({})", errors.join("; "))?;
+            }
+            Ok(())
+        }
+    }
+}
+
+impl Error {
+
+    fn fmt_tree(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        if let Some(errors) = self.errors.as_ref() {
+            for (index, (k, v)) in errors.iter().enumerate() {
+                let branch = if index + 1 == errors.len() { "└──" } else { "├──" };
+                write!(f, "\n{} {}: {}", branch, k, v)?;
+            }
+        }
+        let incident_id = if incident::mode().covers(self.code) { Some(self.incident_id()) } else { self.generated_incident_id() };
+        if let Some(incident_id) = incident_id {
+            write!(f, "\n    incident: {}", incident_id)?;
+        }
+        for context in self.contexts().iter().rev() {
+            write!(f, "\n    while {}", context)?;
+        }
+        for frame in self.frames() {
+            write!(f, "\n    at {}", frame)?;
+        }
+        for (index, attempt) in self.attempts().iter().enumerate() {
+            write!(f, "\n    attempt {}: {} {}", index + 1, attempt.code, attempt.message())?;
+        }
+        if let Some(backtrace) = self.backtrace() {
+            write!(f, "\n    backtrace:")?;
+            for line in backtrace.to_string().lines() {
+                write!(f, "\n    {}", line)?;
+            }
+        }
+        if let Some(cause) = self.cause() {
+            write!(f, "\nCaused by: {:#}", cause)?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for Error {
+
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        self.cause().map(|cause| cause as &(dyn std::error::Error + 'static))
     }
 }
 
-impl std::error::Error for Error { }
+fn empty_map() -> &'static IndexMap<String, String> {
+    static EMPTY: OnceLock<IndexMap<String, String>> = OnceLock::new();
+    EMPTY.get_or_init(IndexMap::new)
+}
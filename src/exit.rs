@@ -0,0 +1,75 @@
+use std::process::{ExitCode, Termination};
+use crate::{Error, Result};
+
+pub const EX_DATAERR: u8 = 65;
+
+pub const EX_NOINPUT: u8 = 66;
+
+pub const EX_SOFTWARE: u8 = 70;
+
+pub const EX_NOPERM: u8 = 77;
+
+/// Maps an error to a `sysexits.h` exit code. Codes without a matching
+/// sysexits value exit with 1.
+pub fn sysexits_code(error: &Error) -> u8 {
+    match error.code {
+        400 => EX_DATAERR,
+        401 | 403 => EX_NOPERM,
+        404 => EX_NOINPUT,
+        500..=599 => EX_SOFTWARE,
+        _ => 1,
+    }
+}
+
+/// Return type for `main` which prints a human readable report and exits
+/// with a code derived from the error.
+///
+/// ```no_run
+/// fn run() -> teo_result::Result<()> {
+///     Ok(())
+/// }
+///
+/// fn main() -> teo_result::Exit {
+///     run().into()
+/// }
+/// ```
+pub struct Exit {
+    result: Result<()>,
+    mapper: fn(&Error) -> u8,
+}
+
+impl Exit {
+
+    pub fn new(result: Result<()>) -> Self {
+        Self {
+            result,
+            mapper: sysexits_code,
+        }
+    }
+
+    pub fn with_mapper(mut self, mapper: fn(&Error) -> u8) -> Self {
+        self.mapper = mapper;
+        self
+    }
+}
+
+impl From<Result<()>> for Exit {
+    fn from(value: Result<()>) -> Self {
+        Self::new(value)
+    }
+}
+
+impl Termination for Exit {
+    fn report(self) -> ExitCode {
+        match self.result {
+            Ok(()) => ExitCode::SUCCESS,
+            Err(error) => {
+                #[cfg(feature = "report")]
+                error.report().eprint();
+                #[cfg(not(feature = "report"))]
+                eprintln!("{:#}", error);
+                ExitCode::from((self.mapper)(&error))
+            }
+        }
+    }
+}
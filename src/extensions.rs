@@ -0,0 +1,93 @@
+use std::any::{Any, TypeId};
+use std::collections::HashMap;
+use std::fmt::{Debug, Formatter};
+use std::sync::Arc;
+
+trait AnyClone: Any + Send + Sync {
+    fn clone_box(&self) -> Box<dyn AnyClone>;
+    fn as_any(&self) -> &dyn Any;
+    fn as_any_mut(&mut self) -> &mut dyn Any;
+    fn into_any(self: Box<Self>) -> Box<dyn Any>;
+}
+
+impl<T: Clone + Send + Sync + 'static> AnyClone for T {
+
+    fn clone_box(&self) -> Box<dyn AnyClone> {
+        Box::new(self.clone())
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+
+    fn into_any(self: Box<Self>) -> Box<dyn Any> {
+        self
+    }
+}
+
+impl Clone for Box<dyn AnyClone> {
+    fn clone(&self) -> Self {
+        (**self).clone_box()
+    }
+}
+
+/// A type map of values attached to an error, keyed by their type.
+///
+/// The map is shared between clones of an error and copied on the first
+/// write, so cloning an error stays cheap.
+#[derive(Clone, Default)]
+pub struct Extensions {
+    map: Option<Arc<HashMap<TypeId, Box<dyn AnyClone>>>>,
+}
+
+impl Extensions {
+
+    pub const fn new() -> Self {
+        Self { map: None }
+    }
+
+    /// Inserts a value, returning the previous value of the same type.
+    pub fn insert<T: Clone + Send + Sync + 'static>(&mut self, value: T) -> Option<T> {
+        let map = Arc::make_mut(self.map.get_or_insert_with(Default::default));
+        map.insert(TypeId::of::<T>(), Box::new(value)).and_then(|previous| previous.into_any().downcast().ok().map(|boxed| *boxed))
+    }
+
+    pub fn get<T: Send + Sync + 'static>(&self) -> Option<&T> {
+        self.map.as_ref()?.get(&TypeId::of::<T>()).and_then(|value| (**value).as_any().downcast_ref())
+    }
+
+    pub fn get_mut<T: Send + Sync + 'static>(&mut self) -> Option<&mut T> {
+        Arc::make_mut(self.map.as_mut()?).get_mut(&TypeId::of::<T>()).and_then(|value| (**value).as_any_mut().downcast_mut())
+    }
+
+    pub fn remove<T: Send + Sync + 'static>(&mut self) -> Option<T> {
+        let map = Arc::make_mut(self.map.as_mut()?);
+        map.remove(&TypeId::of::<T>()).and_then(|value| value.into_any().downcast().ok().map(|boxed| *boxed))
+    }
+
+    pub fn contains<T: Send + Sync + 'static>(&self) -> bool {
+        self.map.as_ref().is_some_and(|map| map.contains_key(&TypeId::of::<T>()))
+    }
+
+    pub fn len(&self) -> usize {
+        self.map.as_ref().map_or(0, |map| map.len())
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn clear(&mut self) {
+        self.map = None;
+    }
+}
+
+impl Debug for Extensions {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Extensions").field("len", &self.len()).finish()
+    }
+}
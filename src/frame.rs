@@ -0,0 +1,112 @@
+use std::fmt::{Display, Formatter};
+use serde::{Deserialize, Serialize};
+
+/// A single frame of a stack trace captured from a foreign runtime.
+///
+/// Frames are stored from the innermost call outwards.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StackFrame {
+    pub file: Option<String>,
+    pub line: Option<u32>,
+    pub column: Option<u32>,
+    pub function: Option<String>,
+}
+
+impl StackFrame {
+
+    pub fn new(file: Option<String>, line: Option<u32>, function: Option<String>) -> Self {
+        Self {
+            file,
+            line,
+            column: None,
+            function,
+        }
+    }
+}
+
+impl Display for StackFrame {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.function.as_deref().unwrap_or("<anonymous>"))?;
+        f.write_str(" (")?;
+        f.write_str(self.file.as_deref().unwrap_or("<unknown>"))?;
+        if let Some(line) = self.line {
+            write!(f, ":{}", line)?;
+            if let Some(column) = self.column {
+                write!(f, ":{}", column)?;
+            }
+        }
+        f.write_str(")")
+    }
+}
+
+/// How deep foreign cause chains are followed, which guards against cycles.
+#[cfg(any(feature = "napi", feature = "wasm-bindgen", feature = "pyo3"))]
+pub(crate) const MAX_CAUSE_DEPTH: usize = 32;
+
+/// Parses a JavaScript `Error.prototype.stack` string. Both the V8 format
+/// (`    at fn (file:line:column)`) and the SpiderMonkey / JavaScriptCore
+/// format (`fn@file:line:column`) are understood.
+#[cfg(any(feature = "napi", feature = "wasm-bindgen", test))]
+pub(crate) fn parse_js_stack(stack: &str) -> Vec<StackFrame> {
+    let v8_lines: Vec<&str> = stack.lines().filter_map(|line| line.trim_start().strip_prefix("at ")).collect();
+    if !v8_lines.is_empty() {
+        return v8_lines.into_iter().map(|line| {
+            if let Some((function, location)) = line.strip_suffix(')').and_then(|line| line.split_once(" (")) {
+                js_frame(Some(function), location)
+            } else {
+                js_frame(None, line)
+            }
+        }).collect();
+    }
+    stack.lines().filter_map(|line| line.split_once('@')).map(|(function, location)| {
+        js_frame(if function.is_empty() { None } else { Some(function) }, location)
+    }).collect()
+}
+
+#[cfg(any(feature = "napi", feature = "wasm-bindgen", test))]
+fn js_frame(function: Option<&str>, location: &str) -> StackFrame {
+    let mut parts = location.rsplitn(3, ':');
+    let (column, line, file) = (parts.next(), parts.next(), parts.next());
+    match (file, line.and_then(|l| l.parse().ok()), column.and_then(|c| c.parse().ok())) {
+        (Some(file), Some(line), Some(column)) => StackFrame {
+            file: Some(file.to_owned()),
+            line: Some(line),
+            column: Some(column),
+            function: function.map(ToOwned::to_owned),
+        },
+        _ => StackFrame::new(Some(location.to_owned()), None, function.map(ToOwned::to_owned)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn frame(function: Option<&str>, file: &str, line: Option<u32>, column: Option<u32>) -> StackFrame {
+        StackFrame { file: Some(file.to_owned()), line, column, function: function.map(ToOwned::to_owned) }
+    }
+
+    #[test]
+    fn parses_v8_stacks() {
+        let stack = "Error: boom\n    at load (/app/user.js:10:15)\n    at /app/index.js:20:3\n    at new Promise (<anonymous>)";
+        assert_eq!(parse_js_stack(stack), vec![
+            frame(Some("load"), "/app/user.js", Some(10), Some(15)),
+            frame(None, "/app/index.js", Some(20), Some(3)),
+            frame(Some("new Promise"), "<anonymous>", None, None),
+        ]);
+    }
+
+    #[test]
+    fn parses_spidermonkey_stacks() {
+        let stack = "load@http://localhost:8080/user.js:10:15\n@http://localhost:8080/index.js:20:3\n";
+        assert_eq!(parse_js_stack(stack), vec![
+            frame(Some("load"), "http://localhost:8080/user.js", Some(10), Some(15)),
+            frame(None, "http://localhost:8080/index.js", Some(20), Some(3)),
+        ]);
+    }
+
+    #[test]
+    fn ignores_messages_without_frames() {
+        assert!(parse_js_stack("Error: boom").is_empty());
+    }
+}
@@ -0,0 +1,129 @@
+//! Incident IDs and creation timestamps.
+//!
+//! Errors covered by the [`IncidentMode`] record their creation time and
+//! generate their incident ID when they are constructed, so that clones
+//! logged and sent to clients carry the same ID. Errors which aren't covered
+//! pay neither cost.
+//!
+//! With the `ulid` feature incident IDs are ULIDs sharing the creation
+//! timestamp. Otherwise they are opaque 128-bit hex strings.
+
+use std::sync::atomic::{AtomicU8, Ordering};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+static MODE: AtomicU8 = AtomicU8::new(IncidentMode::Disabled as u8);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(u8)]
+pub enum IncidentMode {
+    Disabled,
+    ServerErrors,
+    All,
+}
+
+impl IncidentMode {
+
+    pub fn covers(&self, code: u16) -> bool {
+        match self {
+            IncidentMode::Disabled => false,
+            IncidentMode::ServerErrors => code >= 500,
+            IncidentMode::All => true,
+        }
+    }
+}
+
+pub fn set_mode(mode: IncidentMode) {
+    MODE.store(mode as u8, Ordering::Relaxed);
+}
+
+pub fn mode() -> IncidentMode {
+    match MODE.load(Ordering::Relaxed) {
+        1 => IncidentMode::ServerErrors,
+        2 => IncidentMode::All,
+        _ => IncidentMode::Disabled,
+    }
+}
+
+#[cfg(feature = "ulid")]
+pub(crate) fn generate_incident_id(created_at: Option<SystemTime>) -> String {
+    ulid::Ulid::from_datetime(created_at.unwrap_or_else(SystemTime::now)).to_string()
+}
+
+/// Generates an opaque 128-bit hex identifier. It is unique enough to find a
+/// log line, but not suitable for security purposes.
+#[cfg(not(feature = "ulid"))]
+pub(crate) fn generate_incident_id(created_at: Option<SystemTime>) -> String {
+    use std::collections::hash_map::RandomState;
+    use std::hash::{BuildHasher, Hasher};
+    use std::sync::atomic::AtomicU64;
+    static COUNTER: AtomicU64 = AtomicU64::new(0);
+    let nanos = created_at.unwrap_or_else(SystemTime::now).duration_since(UNIX_EPOCH).map(|duration| duration.as_nanos() as u64).unwrap_or_default();
+    let count = COUNTER.fetch_add(1, Ordering::Relaxed);
+    let mut high = RandomState::new().build_hasher();
+    high.write_u64(nanos);
+    high.write_u64(count);
+    let mut low = RandomState::new().build_hasher();
+    low.write_u64(count);
+    low.write_u64(nanos);
+    format!("{:016x}{:016x}", high.finish(), low.finish())
+}
+
+/// Formats a timestamp as RFC 3339 in UTC with millisecond precision, e.g.
+/// `2024-05-01T12:30:00.000Z`.
+pub fn format_timestamp(time: SystemTime) -> String {
+    let duration = time.duration_since(UNIX_EPOCH).unwrap_or_default();
+    let seconds = duration.as_secs();
+    let (year, month, day) = civil_from_days((seconds / 86400) as i64);
+    let seconds_of_day = seconds % 86400;
+    format!(
+        "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}.{:03}Z",
+        year, month, day,
+        seconds_of_day / 3600, seconds_of_day / 60 % 60, seconds_of_day % 60,
+        duration.subsec_millis(),
+    )
+}
+
+/// Parses timestamps produced by [`format_timestamp`]. The fraction is
+/// optional and may have any precision.
+pub fn parse_timestamp(input: &str) -> Option<SystemTime> {
+    let input = input.strip_suffix('Z')?;
+    let (date, time) = input.split_once('T')?;
+    let mut date = date.splitn(3, '-').map(|part| part.parse::<i64>().ok());
+    let (year, month, day) = (date.next()??, date.next()??, date.next()??);
+    let (time, fraction) = time.split_once('.').unwrap_or((time, ""));
+    let mut time = time.splitn(3, ':').map(|part| part.parse::<u64>().ok());
+    let (hour, minute, second) = (time.next()??, time.next()??, time.next()??);
+    if !(1..=12).contains(&month) || !(1..=31).contains(&day) || hour > 23 || minute > 59 || second > 60 {
+        return None;
+    }
+    let nanos = if fraction.is_empty() {
+        0
+    } else {
+        let digits: String = fraction.chars().chain(std::iter::repeat('0')).take(9).collect();
+        digits.parse::<u32>().ok()?
+    };
+    let seconds = u64::try_from(days_from_civil(year, month, day)).ok()? * 86400 + hour * 3600 + minute * 60 + second;
+    Some(UNIX_EPOCH + Duration::new(seconds, nanos))
+}
+
+// Howard Hinnant's algorithms for the proleptic Gregorian calendar
+fn civil_from_days(days: i64) -> (i64, u32, u32) {
+    let days = days + 719468;
+    let era = days.div_euclid(146097);
+    let day_of_era = days.rem_euclid(146097);
+    let year_of_era = (day_of_era - day_of_era / 1460 + day_of_era / 36524 - day_of_era / 146096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month_index = (5 * day_of_year + 2) / 153;
+    let day = (day_of_year - (153 * month_index + 2) / 5 + 1) as u32;
+    let month = if month_index < 10 { month_index + 3 } else { month_index - 9 } as u32;
+    (year_of_era + era * 400 + i64::from(month <= 2), month, day)
+}
+
+fn days_from_civil(year: i64, month: i64, day: i64) -> i64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let year_of_era = year.rem_euclid(400);
+    let day_of_year = (153 * (if month > 2 { month - 3 } else { month + 9 }) + 2) / 5 + day - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    era * 146097 + day_of_era - 719468
+}
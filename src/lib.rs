@@ -1,6 +1,32 @@
 mod error;
+mod extensions;
+mod frame;
 mod result;
+mod status;
 mod conversion;
+pub mod context;
+pub mod envelope;
+pub mod exit;
+pub mod incident;
+pub mod redaction;
+pub mod render;
+#[cfg(feature = "report")]
+mod report;
+#[cfg(feature = "tokio")]
+pub mod retry;
+#[cfg(feature = "prost")]
+pub mod rpc;
+#[cfg(feature = "tracing")]
+pub mod trace;
+#[cfg(feature = "opentelemetry")]
+mod otel;
 
-pub use error::{Error, ErrorSerializable};
+pub use error::{Error, ErrorSerializable, Lossless};
+pub use extensions::Extensions;
+pub use frame::StackFrame;
 pub use result::{Result, ResultExt};
+pub use exit::Exit;
+#[cfg(feature = "report")]
+pub use report::Report;
+#[cfg(feature = "ffi")]
+pub use conversion::ffi::TeoError;
@@ -0,0 +1,76 @@
+use opentelemetry::KeyValue;
+use opentelemetry::trace::{get_active_span, Span, SpanRef, Status};
+use crate::Error;
+
+// the active span is only reachable as a `SpanRef`, which has the recording
+// methods of `Span` without implementing it
+trait Recorder {
+    fn add_exception(&mut self, attributes: Vec<KeyValue>);
+    fn set_error(&mut self, status: Status);
+}
+
+impl<S: Span> Recorder for &mut S {
+
+    fn add_exception(&mut self, attributes: Vec<KeyValue>) {
+        Span::add_event(*self, "exception", attributes);
+    }
+
+    fn set_error(&mut self, status: Status) {
+        Span::set_status(*self, status);
+    }
+}
+
+impl Recorder for SpanRef<'_> {
+
+    fn add_exception(&mut self, attributes: Vec<KeyValue>) {
+        SpanRef::add_event(self, "exception", attributes);
+    }
+
+    fn set_error(&mut self, status: Status) {
+        SpanRef::set_status(self, status);
+    }
+}
+
+impl Error {
+
+    /// Attributes following the OpenTelemetry exception semantic
+    /// conventions. `exception.stacktrace` is only present if the error
+    /// carries frames, a backtrace or a cause. The internal message is
+    /// attached as `teo.error.internal_message` and annotations as
+    /// `teo.error.annotation.<key>`.
+    pub fn otel_attributes(&self) -> Vec<KeyValue> {
+        let mut attributes = vec![
+            KeyValue::new("exception.type", self.inferred_title().into_owned()),
+            KeyValue::new("exception.message", self.message.clone()),
+            KeyValue::new("http.response.status_code", self.code as i64),
+        ];
+        if let Some(internal_message) = self.internal_message() {
+            attributes.push(KeyValue::new("teo.error.internal_message", internal_message.to_owned()));
+        }
+        for (key, value) in self.annotations() {
+            attributes.push(KeyValue::new(format!("teo.error.annotation.{}", key), value.clone()));
+        }
+        if !self.frames().is_empty() || self.backtrace().is_some() || self.cause().is_some() {
+            attributes.push(KeyValue::new("exception.stacktrace", format!("{:#}", self)));
+        }
+        attributes
+    }
+
+    /// Records the error as an `exception` event on `span`. The span status
+    /// is set to error for 5xx errors.
+    pub fn record_otel_exception_on<S: Span>(&self, span: &mut S) {
+        self.record_exception(span);
+    }
+
+    /// Records the error on the currently active span.
+    pub fn record_otel_exception(&self) {
+        get_active_span(|span| self.record_exception(span));
+    }
+
+    fn record_exception(&self, mut recorder: impl Recorder) {
+        recorder.add_exception(self.otel_attributes());
+        if self.code >= 500 {
+            recorder.set_error(Status::error(self.message.clone()));
+        }
+    }
+}
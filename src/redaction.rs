@@ -0,0 +1,114 @@
+use std::borrow::Cow;
+use std::sync::{Arc, LazyLock, RwLock};
+use std::sync::atomic::{AtomicBool, Ordering};
+use crate::Error;
+
+static GLOBAL_POLICY: LazyLock<RwLock<Arc<RedactionPolicy>>> = LazyLock::new(|| RwLock::new(Arc::new(RedactionPolicy::disabled())));
+
+// mirrors `redact_server_errors` of the global policy, so that checking it
+// doesn't take the lock
+static GLOBAL_REDACT_SERVER_ERRORS: AtomicBool = AtomicBool::new(false);
+
+/// Decides which details of an error may be shown to clients.
+///
+/// The internal message is always removed from the public view.
+/// Redacted 5xx errors lose their path errors, and their message is
+/// replaced by `generic_message`, or by the status title if it's not set.
+/// An incident ID is attached so that the full error can be found in logs.
+/// Values of path errors matching `sensitive_paths` are replaced by `mask`
+/// regardless of the status code. A `*` in a pattern matches any sequence of
+/// characters, so `*.password` matches `user.password`.
+#[derive(Debug, Clone)]
+pub struct RedactionPolicy {
+    pub redact_server_errors: bool,
+    pub generic_message: Option<String>,
+    pub sensitive_paths: Vec<String>,
+    pub mask: String,
+}
+
+impl RedactionPolicy {
+
+    pub fn disabled() -> Self {
+        Self {
+            redact_server_errors: false,
+            generic_message: None,
+            sensitive_paths: Vec::new(),
+            mask: "[REDACTED]".to_owned(),
+        }
+    }
+
+    pub fn production() -> Self {
+        Self {
+            redact_server_errors: true,
+            generic_message: None,
+            sensitive_paths: vec!["password".to_owned(), "*.password".to_owned()],
+            mask: "[REDACTED]".to_owned(),
+        }
+    }
+
+    pub fn redacts(&self, error: &Error) -> bool {
+        redacts(self.redact_server_errors, error)
+    }
+
+    pub fn is_sensitive(&self, path: &str) -> bool {
+        self.sensitive_paths.iter().any(|pattern| wildcard_match(pattern.as_bytes(), path.as_bytes()))
+    }
+
+    /// Returns the public view of an error. The error itself is borrowed if
+    /// there is nothing to remove or mask. Otherwise the view is a new error
+    /// with only the fields shown to clients: the code, message, path errors,
+    /// incident ID, creation time, retry-after hint and headers.
+    pub fn apply<'a>(&self, error: &'a Error) -> Cow<'a, Error> {
+        match self.redacted(error) {
+            Some(view) => Cow::Owned(view),
+            None if error.internal_message().is_none() => Cow::Borrowed(error),
+            None => Cow::Owned(error.public_view(error.message.clone(), error.errors.clone())),
+        }
+    }
+
+    /// Returns the public view of an error if the policy redacts it or masks
+    /// one of its path errors.
+    pub(crate) fn redacted(&self, error: &Error) -> Option<Error> {
+        if self.redacts(error) {
+            // generated on the original so logs of it carry the same ID
+            error.incident_id();
+            let message = self.generic_message.clone().unwrap_or_else(|| error.title_text());
+            return Some(error.public_view(message, None));
+        }
+        let errors = error.errors()?;
+        if !errors.keys().any(|path| self.is_sensitive(path)) {
+            return None;
+        }
+        let errors = errors.iter().map(|(path, message)| {
+            (path.clone(), if self.is_sensitive(path) { self.mask.clone() } else { message.clone() })
+        }).collect();
+        Some(error.public_view(error.message.clone(), Some(errors)))
+    }
+}
+
+fn redacts(redact_server_errors: bool, error: &Error) -> bool {
+    redact_server_errors && error.code >= 500
+}
+
+pub fn set_global_policy(policy: RedactionPolicy) {
+    let mut global_policy = GLOBAL_POLICY.write().unwrap();
+    GLOBAL_REDACT_SERVER_ERRORS.store(policy.redact_server_errors, Ordering::Relaxed);
+    *global_policy = Arc::new(policy);
+}
+
+/// Whether the global policy redacts `error`, without taking its lock.
+pub(crate) fn globally_redacts(error: &Error) -> bool {
+    redacts(GLOBAL_REDACT_SERVER_ERRORS.load(Ordering::Relaxed), error)
+}
+
+pub fn global_policy() -> Arc<RedactionPolicy> {
+    GLOBAL_POLICY.read().unwrap().clone()
+}
+
+fn wildcard_match(pattern: &[u8], text: &[u8]) -> bool {
+    match pattern.split_first() {
+        None => text.is_empty(),
+        Some((b'*', rest)) => (0..=text.len()).any(|index| wildcard_match(rest, &text[index..])),
+        Some((char, rest)) => text.split_first().is_some_and(|(first, text)| first == char && wildcard_match(rest, text)),
+    }
+}
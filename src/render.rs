@@ -0,0 +1,160 @@
+use serde_json::{json, Value};
+use crate::{Error, ErrorSerializable};
+use crate::redaction;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RenderFormat {
+    Json,
+    ProblemJson,
+    Html,
+    PlainText,
+    Xml,
+}
+
+impl RenderFormat {
+
+    /// Picks the format preferred by an `Accept` header value. Falls back to
+    /// JSON if nothing supported is acceptable.
+    pub fn negotiate(accept: &str) -> Self {
+        let mut ranges: Vec<(&str, f32)> = accept.split(',').filter_map(|range| {
+            let mut parts = range.split(';');
+            let media_type = parts.next()?.trim();
+            let quality = parts.filter_map(|param| param.trim().strip_prefix("q=")).next().and_then(|q| q.trim().parse().ok()).unwrap_or(1.0);
+            if media_type.is_empty() || quality <= 0.0 { None } else { Some((media_type, quality)) }
+        }).collect();
+        // stable, so equally weighted ranges keep the client's order
+        ranges.sort_by(|a, b| b.1.total_cmp(&a.1));
+        ranges.into_iter().find_map(|(media_type, _)| Self::from_media_type(media_type)).unwrap_or(RenderFormat::Json)
+    }
+
+    fn from_media_type(media_type: &str) -> Option<Self> {
+        match media_type.to_ascii_lowercase().as_str() {
+            "application/json" | "application/*" | "*/*" => Some(RenderFormat::Json),
+            "application/problem+json" => Some(RenderFormat::ProblemJson),
+            "text/html" | "application/xhtml+xml" => Some(RenderFormat::Html),
+            "text/plain" | "text/*" => Some(RenderFormat::PlainText),
+            "application/xml" | "text/xml" | "application/problem+xml" => Some(RenderFormat::Xml),
+            _ => None,
+        }
+    }
+
+    pub fn content_type(&self) -> &'static str {
+        match self {
+            RenderFormat::Json => "application/json",
+            RenderFormat::ProblemJson => "application/problem+json",
+            RenderFormat::Html => "text/html; charset=utf-8",
+            RenderFormat::PlainText => "text/plain; charset=utf-8",
+            RenderFormat::Xml => "application/xml; charset=utf-8",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Rendered {
+    pub content_type: &'static str,
+    pub body: String,
+}
+
+impl Error {
+
+    pub fn render(&self, accept: &str) -> Rendered {
+        self.render_as(RenderFormat::negotiate(accept))
+    }
+
+    /// Renders the public view of the error under the global redaction
+    /// policy.
+    pub fn render_as(&self, format: RenderFormat) -> Rendered {
+        self.ensure_incident_id();
+        let public = redaction::global_policy().apply(self);
+        let body = match format {
+            RenderFormat::Json => serde_json::to_string(&ErrorSerializable::unredacted(&public)).unwrap(),
+            RenderFormat::ProblemJson => public.render_problem_json(),
+            RenderFormat::Html => public.render_html(),
+            RenderFormat::PlainText => public.render_plain_text(),
+            RenderFormat::Xml => public.render_xml(),
+        };
+        Rendered { content_type: format.content_type(), body }
+    }
+
+    fn status_line(&self) -> String {
+        format!("{} {}", self.code, self.title_text())
+    }
+
+    fn render_problem_json(&self) -> String {
+        let mut problem = json!({
+            "type": "about:blank",
+            "title": self.title_text(),
+            "status": self.code,
+            "detail": self.message(),
+        });
+        if let Some(errors) = self.errors() {
+            problem["errors"] = Value::Object(errors.iter().map(|(k, v)| (k.clone(), Value::String(v.clone()))).collect());
+        }
+        if let Some(incident_id) = self.generated_incident_id() {
+            problem["incident_id"] = Value::String(incident_id.to_owned());
+        }
+        problem.to_string()
+    }
+
+    fn render_html(&self) -> String {
+        let status_line = escape(&self.status_line());
+        let mut body = format!("<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n<meta charset=\"utf-8\">\n<title>{status_line}</title>\n</head>\n<body>\n<h1>{status_line}</h1>\n<p>{}</p>\n", escape(self.message()));
+        if let Some(errors) = self.errors() {
+            body.push_str("<ul>\n");
+            for (k, v) in errors {
+                body.push_str(&format!("<li><code>{}</code>: {}</li>\n", escape(k), escape(v)));
+            }
+            body.push_str("</ul>\n");
+        }
+        if let Some(incident_id) = self.generated_incident_id() {
+            body.push_str(&format!("<p>Incident ID: <code>{}</code></p>\n", escape(incident_id)));
+        }
+        body.push_str("</body>\n</html>\n");
+        body
+    }
+
+    fn render_plain_text(&self) -> String {
+        let mut body = format!("{}: {}\n", self.status_line(), self.message());
+        if let Some(errors) = self.errors() {
+            for (k, v) in errors {
+                body.push_str(&format!("{}: {}\n", k, v));
+            }
+        }
+        if let Some(incident_id) = self.generated_incident_id() {
+            body.push_str(&format!("Incident ID: {}\n", incident_id));
+        }
+        body
+    }
+
+    fn render_xml(&self) -> String {
+        let mut body = format!("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<error>\n<code>{}</code>\n<title>{}</title>\n<message>{}</message>\n", self.code, escape(&self.inferred_title()), escape(self.message()));
+        if let Some(errors) = self.errors() {
+            body.push_str("<errors>\n");
+            for (k, v) in errors {
+                body.push_str(&format!("<error path=\"{}\">{}</error>\n", escape(k), escape(v)));
+            }
+            body.push_str("</errors>\n");
+        }
+        if let Some(incident_id) = self.generated_incident_id() {
+            body.push_str(&format!("<incidentId>{}</incidentId>\n", escape(incident_id)));
+        }
+        body.push_str("</error>\n");
+        body
+    }
+}
+
+/// Escapes text for both HTML and XML content and attribute values.
+fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for char in text.chars() {
+        match char {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            _ => escaped.push(char),
+        }
+    }
+    escaped
+}
@@ -0,0 +1,162 @@
+use std::fmt::{Display, Formatter};
+use std::io::IsTerminal;
+use crate::Error;
+
+const DEFAULT_WIDTH: usize = 80;
+
+const RESET: &str = "\x1b[0m";
+const BOLD_RED: &str = "\x1b[1;31m";
+const BOLD_YELLOW: &str = "\x1b[1;33m";
+const CYAN: &str = "\x1b[36m";
+const GREEN: &str = "\x1b[32m";
+const BLUE: &str = "\x1b[34m";
+const DIM: &str = "\x1b[2m";
+
+/// A terminal diagnostic report of an error.
+///
+/// Color and width are detected from stderr. Color is disabled when stderr
+/// is not a terminal or `NO_COLOR` is set.
+pub struct Report<'a> {
+    error: &'a Error,
+    help: Option<String>,
+    hint: Option<String>,
+    color: bool,
+    width: usize,
+}
+
+impl<'a> Report<'a> {
+
+    pub fn new(error: &'a Error) -> Self {
+        let stderr = std::io::stderr();
+        Self {
+            error,
+            help: None,
+            hint: None,
+            color: stderr.is_terminal() && std::env::var_os("NO_COLOR").is_none(),
+            width: terminal_size::terminal_size_of(&stderr).map(|(width, _)| width.0 as usize).unwrap_or(DEFAULT_WIDTH),
+        }
+    }
+
+    pub fn help(mut self, help: impl Into<String>) -> Self {
+        self.help = Some(help.into());
+        self
+    }
+
+    pub fn hint(mut self, hint: impl Into<String>) -> Self {
+        self.hint = Some(hint.into());
+        self
+    }
+
+    pub fn color(mut self, color: bool) -> Self {
+        self.color = color;
+        self
+    }
+
+    pub fn width(mut self, width: usize) -> Self {
+        self.width = width;
+        self
+    }
+
+    pub fn eprint(&self) {
+        eprint!("{}", self);
+    }
+
+    fn paint(&self, style: &str, text: &str) -> String {
+        if self.color {
+            format!("{}{}{}", style, text, RESET)
+        } else {
+            text.to_owned()
+        }
+    }
+
+    fn title_style(error: &Error) -> &'static str {
+        if error.code >= 500 { BOLD_RED } else { BOLD_YELLOW }
+    }
+
+    fn write_error(&self, f: &mut Formatter<'_>, error: &Error, indent: &str) -> std::fmt::Result {
+        for line in wrap(error.message(), self.width.saturating_sub(indent.len() + 2)) {
+            writeln!(f, "{}  {}", indent, line)?;
+        }
+        if let Some(internal_message) = error.internal_message() {
+            for line in wrap(internal_message, self.width.saturating_sub(indent.len() + 2)) {
+                writeln!(f, "{}  {}", indent, self.paint(DIM, &line))?;
+            }
+        }
+        if let Some(errors) = error.errors().filter(|errors| !errors.is_empty()) {
+            writeln!(f, "{}", indent)?;
+            for (index, (path, message)) in errors.iter().enumerate() {
+                let last = index + 1 == errors.len();
+                writeln!(f, "{}  {} {}", indent, if last { "└─" } else { "├─" }, self.paint(CYAN, path))?;
+                let continuation = if last { "     " } else { "│    " };
+                for line in wrap(message, self.width.saturating_sub(indent.len() + 7)) {
+                    writeln!(f, "{}  {}{}", indent, continuation, line)?;
+                }
+            }
+        }
+        for context in error.contexts().iter().rev() {
+            writeln!(f, "{}  {}", indent, self.paint(DIM, &format!("while {}", context)))?;
+        }
+        for frame in error.frames() {
+            writeln!(f, "{}  {}", indent, self.paint(DIM, &format!("at {}", frame)))?;
+        }
+        for (index, attempt) in error.attempts().iter().enumerate() {
+            writeln!(f, "{}  {}", indent, self.paint(DIM, &format!("attempt {}: {} {}", index + 1, attempt.code, attempt.message())))?;
+        }
+        Ok(())
+    }
+}
+
+impl Display for Report<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let error = self.error;
+        writeln!(f, "{}", self.paint(Self::title_style(error), &format!("✖ {} {}", error.code, error.inferred_title())))?;
+        self.write_error(f, error, "")?;
+        let mut cause = error.cause();
+        while let Some(current) = cause {
+            writeln!(f)?;
+            writeln!(f, "  {}", self.paint(Self::title_style(current), &format!("Caused by: {} {}", current.code, current.inferred_title())))?;
+            self.write_error(f, current, "  ")?;
+            cause = current.cause();
+        }
+        if let Some(help) = &self.help {
+            writeln!(f)?;
+            for (index, line) in wrap(help, self.width.saturating_sub(8)).into_iter().enumerate() {
+                writeln!(f, "  {} {}", if index == 0 { self.paint(GREEN, "help:") } else { "     ".to_owned() }, line)?;
+            }
+        }
+        if let Some(hint) = &self.hint {
+            writeln!(f)?;
+            for (index, line) in wrap(hint, self.width.saturating_sub(8)).into_iter().enumerate() {
+                writeln!(f, "  {} {}", if index == 0 { self.paint(BLUE, "hint:") } else { "     ".to_owned() }, line)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Error {
+
+    pub fn report(&self) -> Report<'_> {
+        Report::new(self)
+    }
+}
+
+/// Wraps text at word boundaries. Words longer than `width` are kept whole.
+fn wrap(text: &str, width: usize) -> Vec<String> {
+    let width = width.max(20);
+    let mut lines = Vec::new();
+    for paragraph in text.lines() {
+        let mut line = String::new();
+        for word in paragraph.split_whitespace() {
+            if !line.is_empty() && line.chars().count() + 1 + word.chars().count() > width {
+                lines.push(std::mem::take(&mut line));
+            }
+            if !line.is_empty() {
+                line.push(' ');
+            }
+            line.push_str(word);
+        }
+        lines.push(line);
+    }
+    lines
+}
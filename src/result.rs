@@ -9,6 +9,19 @@ pub trait ResultExt<T> {
     fn error_path_prefixed(self, prefix: impl AsRef<str>) -> Result<T>;
 
     fn alter_error_code(self, code: u16) -> Result<T>;
+
+    fn context(self, context: impl Into<String>) -> Result<T>;
+
+    /// Like `context`, but builds the breadcrumb only on error.
+    fn with_context<C, F>(self, f: F) -> Result<T> where C: Into<String>, F: FnOnce() -> C;
+
+    fn error_internal_message(self, internal_message: impl Into<String>) -> Result<T>;
+
+    /// Like `error_internal_message`, but builds the detail only on error.
+    fn with_error_internal_message<F>(self, f: F) -> Result<T> where F: FnOnce(&Error) -> String;
+
+    #[cfg(feature = "tracing")]
+    fn inspect_teo_err_trace(self) -> Result<T>;
 }
 
 impl<T> ResultExt<T> for std::result::Result<T, Error> {
@@ -36,4 +49,31 @@ impl<T> ResultExt<T> for std::result::Result<T, Error> {
             }),
         }
     }
+
+    fn context(self, context: impl Into<String>) -> Self {
+        self.map_err(|e| e.context(context))
+    }
+
+    fn with_context<C, F>(self, f: F) -> Self where C: Into<String>, F: FnOnce() -> C {
+        self.map_err(|e| e.context(f()))
+    }
+
+    fn error_internal_message(self, internal_message: impl Into<String>) -> Self {
+        self.map_err(|e| e.with_internal_message(internal_message))
+    }
+
+    fn with_error_internal_message<F>(self, f: F) -> Self where F: FnOnce(&Error) -> String {
+        self.map_err(|e| {
+            let internal_message = f(&e);
+            e.with_internal_message(internal_message)
+        })
+    }
+
+    #[cfg(feature = "tracing")]
+    fn inspect_teo_err_trace(self) -> Self {
+        if let Err(e) = &self {
+            e.record(&tracing::Span::current());
+        }
+        self
+    }
 }
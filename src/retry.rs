@@ -0,0 +1,214 @@
+//! Retrying fallible async operations, driven by [`Error::is_retryable`].
+//!
+//! ```
+//! use std::future::{ready, Future};
+//! use std::pin::Pin;
+//! use std::sync::Mutex;
+//! use std::time::{Duration, Instant};
+//! use teo_result::Error;
+//! use teo_result::retry::{retry, Attempts, Clock, RetryPolicy};
+//!
+//! // advances virtual time instead of sleeping
+//! struct MockClock(Mutex<Instant>);
+//!
+//! impl Clock for MockClock {
+//!     fn now(&self) -> Instant {
+//!         *self.0.lock().unwrap()
+//!     }
+//!     fn sleep(&self, duration: Duration) -> Pin<Box<dyn Future<Output = ()> + Send>> {
+//!         *self.0.lock().unwrap() += duration;
+//!         Box::pin(ready(()))
+//!     }
+//! }
+//!
+//! let policy = RetryPolicy::new().max_attempts(3).clock(MockClock(Mutex::new(Instant::now())));
+//! let runtime = tokio::runtime::Builder::new_current_thread().enable_time().build().unwrap();
+//! let error = runtime.block_on(retry(&policy, || async { Err::<(), _>(Error::service_unavailable(None)) })).unwrap_err();
+//! assert_eq!(error.code, 503);
+//! assert_eq!(error.extensions().get::<Attempts>().unwrap().0.len(), 3);
+//! ```
+
+use std::collections::hash_map::RandomState;
+use std::future::Future;
+use std::hash::{BuildHasher, Hasher};
+use std::pin::Pin;
+use std::sync::Arc;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+use crate::{Error, Result};
+
+type Classifier = Arc<dyn Fn(&Error) -> bool + Send + Sync>;
+
+/// A source of time, replaceable to test retries without waiting.
+pub trait Clock: Send + Sync {
+    fn now(&self) -> Instant;
+    fn sleep(&self, duration: Duration) -> Pin<Box<dyn Future<Output = ()> + Send>>;
+}
+
+/// Sleeps on the tokio timer.
+pub struct TokioClock;
+
+impl Clock for TokioClock {
+
+    fn now(&self) -> Instant {
+        Instant::now()
+    }
+
+    fn sleep(&self, duration: Duration) -> Pin<Box<dyn Future<Output = ()> + Send>> {
+        Box::pin(tokio::time::sleep(duration))
+    }
+}
+
+/// Every failed attempt of a retried operation, attached to the returned
+/// error's extensions. Debug output, the alternate Display form and the
+/// report list them as `attempt N: <code> <message>`.
+#[derive(Debug, Clone)]
+pub struct Attempts(pub Vec<Error>);
+
+/// Exponential backoff with jitter. Defaults to 3 attempts, starting at
+/// 100ms and doubling up to 10s, without an elapsed time limit.
+///
+/// A retry-after hint on the error replaces the computed delay. Since
+/// retrying before the hint has passed is pointless, the policy gives up
+/// if the hint is longer than the maximum delay or the time left. Errors
+/// are retried if [`Error::is_retryable`] returns `true`, unless a
+/// classifier is set.
+#[derive(Clone)]
+pub struct RetryPolicy {
+    max_attempts: u32,
+    max_elapsed: Option<Duration>,
+    initial_delay: Duration,
+    max_delay: Duration,
+    multiplier: f64,
+    jitter: bool,
+    classifier: Option<Classifier>,
+    clock: Arc<dyn Clock>,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: 3,
+            max_elapsed: None,
+            initial_delay: Duration::from_millis(100),
+            max_delay: Duration::from_secs(10),
+            multiplier: 2.0,
+            jitter: true,
+            classifier: None,
+            clock: Arc::new(TokioClock),
+        }
+    }
+}
+
+impl RetryPolicy {
+
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn max_attempts(mut self, max_attempts: u32) -> Self {
+        self.max_attempts = max_attempts.max(1);
+        self
+    }
+
+    pub fn max_elapsed(mut self, max_elapsed: Duration) -> Self {
+        self.max_elapsed = Some(max_elapsed);
+        self
+    }
+
+    pub fn initial_delay(mut self, initial_delay: Duration) -> Self {
+        self.initial_delay = initial_delay;
+        self
+    }
+
+    pub fn max_delay(mut self, max_delay: Duration) -> Self {
+        self.max_delay = max_delay;
+        self
+    }
+
+    pub fn multiplier(mut self, multiplier: f64) -> Self {
+        self.multiplier = multiplier;
+        self
+    }
+
+    /// When enabled, each delay is picked at random between half and all of
+    /// the computed backoff.
+    pub fn jitter(mut self, jitter: bool) -> Self {
+        self.jitter = jitter;
+        self
+    }
+
+    pub fn classifier(mut self, classifier: impl Fn(&Error) -> bool + Send + Sync + 'static) -> Self {
+        self.classifier = Some(Arc::new(classifier));
+        self
+    }
+
+    pub fn clock(mut self, clock: impl Clock + 'static) -> Self {
+        self.clock = Arc::new(clock);
+        self
+    }
+
+    fn should_retry(&self, error: &Error) -> bool {
+        match &self.classifier {
+            Some(classifier) => classifier(error),
+            None => error.is_retryable(),
+        }
+    }
+
+    /// The delay before the attempt following `attempt`, counted from 1.
+    fn delay(&self, attempt: u32, error: &Error) -> Duration {
+        if let Some(retry_after) = error.retry_after() {
+            return retry_after;
+        }
+        let backoff = self.initial_delay.as_secs_f64() * self.multiplier.powi(attempt as i32 - 1);
+        let backoff = backoff.min(self.max_delay.as_secs_f64());
+        let backoff = if self.jitter { backoff * (0.5 + random_fraction() / 2.0) } else { backoff };
+        Duration::try_from_secs_f64(backoff).unwrap_or(self.max_delay)
+    }
+}
+
+fn random_fraction() -> f64 {
+    let mut hasher = RandomState::new().build_hasher();
+    hasher.write_u64(SystemTime::now().duration_since(UNIX_EPOCH).map(|duration| duration.as_nanos() as u64).unwrap_or_default());
+    (hasher.finish() >> 11) as f64 / (1u64 << 53) as f64
+}
+
+/// Runs `operation` until it succeeds, fails with an error the policy does
+/// not retry, or the policy gives up.
+///
+/// An error from the only attempt is returned as is. Otherwise the returned
+/// error has the code of the last attempt, carries the last error as its
+/// cause and every error in its [`Attempts`] extension.
+pub async fn retry<T, F, Fut>(policy: &RetryPolicy, mut operation: F) -> Result<T> where F: FnMut() -> Fut, Fut: Future<Output = Result<T>> {
+    let start = policy.clock.now();
+    let mut attempts = Vec::new();
+    loop {
+        let error = match operation().await {
+            Ok(value) => return Ok(value),
+            Err(error) => error,
+        };
+        let attempt = attempts.len() as u32 + 1;
+        let delay = policy.delay(attempt, &error);
+        let out_of_time = policy.max_elapsed.is_some_and(|max_elapsed| policy.clock.now().duration_since(start) + delay > max_elapsed);
+        let too_long = delay > policy.max_delay;
+        let give_up = attempt >= policy.max_attempts || out_of_time || too_long || !policy.should_retry(&error);
+        attempts.push(error);
+        if give_up {
+            return Err(aggregate(attempts));
+        }
+        policy.clock.sleep(delay).await;
+    }
+}
+
+fn aggregate(mut attempts: Vec<Error>) -> Error {
+    if attempts.len() == 1 {
+        return attempts.pop().unwrap();
+    }
+    let last = attempts.last().unwrap().clone();
+    let mut error = Error::new_with_code(format!("operation failed after {} attempts", attempts.len()), last.code);
+    if let Some(retry_after) = last.retry_after() {
+        error.assign_retry_after(retry_after);
+    }
+    error.assign_cause(last);
+    error.extensions_mut().insert(Attempts(attempts));
+    error
+}
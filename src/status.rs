@@ -0,0 +1,75 @@
+use crate::Error;
+
+/// Generates `x()`, `x_message` and `x_pathed` constructors and an `is_x()`
+/// predicate for every status. Statuses marked `custom` have a hand-written
+/// `x()` constructor taking extra arguments.
+macro_rules! status_families {
+    ($($code:literal $message:literal $name:ident $name_message:ident $name_pathed:ident $is_name:ident $($custom:ident)?;)*) => {
+        impl Error {
+            $(
+                status_families!(@base $code $message $name $($custom)?);
+
+                pub fn $name_message(message: impl Into<String>) -> Self {
+                    Self::new_with_code(message, $code)
+                }
+
+                pub fn $name_pathed(path: impl Into<String>, message: impl Into<String>) -> Self {
+                    Self::new_pathed($message, $code, path, message)
+                }
+
+                pub fn $is_name(&self) -> bool {
+                    self.code == $code
+                }
+            )*
+        }
+    };
+    (@base $code:literal $message:literal $name:ident custom) => {};
+    (@base $code:literal $message:literal $name:ident) => {
+        pub fn $name() -> Self {
+            Self::new_with_code($message, $code)
+        }
+    };
+}
+
+status_families! {
+    400 "value is invalid" invalid_request invalid_request_message invalid_request_pathed is_invalid_request;
+    401 "unauthorized" unauthorized unauthorized_message unauthorized_pathed is_unauthorized;
+    402 "payment required" payment_required payment_required_message payment_required_pathed is_payment_required;
+    403 "forbidden" forbidden forbidden_message forbidden_pathed is_forbidden;
+    404 "not found" not_found not_found_message not_found_pathed is_not_found;
+    405 "method not allowed" method_not_allowed method_not_allowed_message method_not_allowed_pathed is_method_not_allowed custom;
+    406 "not acceptable" not_acceptable not_acceptable_message not_acceptable_pathed is_not_acceptable;
+    407 "proxy authentication required" proxy_authentication_required proxy_authentication_required_message proxy_authentication_required_pathed is_proxy_authentication_required;
+    408 "request timeout" request_timeout request_timeout_message request_timeout_pathed is_request_timeout;
+    409 "conflict" conflict conflict_message conflict_pathed is_conflict;
+    410 "gone" gone gone_message gone_pathed is_gone;
+    411 "length required" length_required length_required_message length_required_pathed is_length_required;
+    412 "precondition failed" precondition_failed precondition_failed_message precondition_failed_pathed is_precondition_failed;
+    413 "payload too large" payload_too_large payload_too_large_message payload_too_large_pathed is_payload_too_large;
+    414 "uri too long" uri_too_long uri_too_long_message uri_too_long_pathed is_uri_too_long;
+    415 "unsupported media type" unsupported_media_type unsupported_media_type_message unsupported_media_type_pathed is_unsupported_media_type;
+    416 "range not satisfiable" range_not_satisfiable range_not_satisfiable_message range_not_satisfiable_pathed is_range_not_satisfiable;
+    417 "expectation failed" expectation_failed expectation_failed_message expectation_failed_pathed is_expectation_failed;
+    418 "i'm a teapot" im_a_teapot im_a_teapot_message im_a_teapot_pathed is_im_a_teapot;
+    421 "misdirected request" misdirected_request misdirected_request_message misdirected_request_pathed is_misdirected_request;
+    422 "unprocessable content" unprocessable_content unprocessable_content_message unprocessable_content_pathed is_unprocessable_content;
+    423 "locked" locked locked_message locked_pathed is_locked;
+    424 "failed dependency" failed_dependency failed_dependency_message failed_dependency_pathed is_failed_dependency;
+    425 "too early" too_early too_early_message too_early_pathed is_too_early;
+    426 "upgrade required" upgrade_required upgrade_required_message upgrade_required_pathed is_upgrade_required;
+    428 "precondition required" precondition_required precondition_required_message precondition_required_pathed is_precondition_required;
+    429 "too many requests" too_many_requests too_many_requests_message too_many_requests_pathed is_too_many_requests custom;
+    431 "request header fields too large" request_header_fields_too_large request_header_fields_too_large_message request_header_fields_too_large_pathed is_request_header_fields_too_large;
+    451 "unavailable for legal reasons" unavailable_for_legal_reasons unavailable_for_legal_reasons_message unavailable_for_legal_reasons_pathed is_unavailable_for_legal_reasons;
+    500 "internal server error" internal_server_error internal_server_error_message internal_server_error_pathed is_internal_server_error;
+    501 "not implemented" not_implemented not_implemented_message not_implemented_pathed is_not_implemented;
+    502 "bad gateway" bad_gateway bad_gateway_message bad_gateway_pathed is_bad_gateway;
+    503 "service unavailable" service_unavailable service_unavailable_message service_unavailable_pathed is_service_unavailable custom;
+    504 "gateway timeout" gateway_timeout gateway_timeout_message gateway_timeout_pathed is_gateway_timeout;
+    505 "http version not supported" http_version_not_supported http_version_not_supported_message http_version_not_supported_pathed is_http_version_not_supported;
+    506 "variant also negotiates" variant_also_negotiates variant_also_negotiates_message variant_also_negotiates_pathed is_variant_also_negotiates;
+    507 "insufficient storage" insufficient_storage insufficient_storage_message insufficient_storage_pathed is_insufficient_storage;
+    508 "loop detected" loop_detected loop_detected_message loop_detected_pathed is_loop_detected;
+    510 "not extended" not_extended not_extended_message not_extended_pathed is_not_extended;
+    511 "network authentication required" network_authentication_required network_authentication_required_message network_authentication_required_pathed is_network_authentication_required;
+}
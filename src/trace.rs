@@ -0,0 +1,102 @@
+use std::sync::RwLock;
+use tracing::{Level, Span};
+use crate::Error;
+
+static LEVEL_POLICY: RwLock<fn(&Error) -> Level> = RwLock::new(default_level);
+
+/// 5xx errors are recorded at `ERROR`, 4xx errors at `WARN` and anything
+/// else at `DEBUG`.
+pub fn default_level(error: &Error) -> Level {
+    match error.code {
+        500..=599 => Level::ERROR,
+        400..=499 => Level::WARN,
+        _ => Level::DEBUG,
+    }
+}
+
+/// Replaces the policy which decides the level `Error::record` emits at.
+pub fn set_level_policy(policy: fn(&Error) -> Level) {
+    *LEVEL_POLICY.write().unwrap() = policy;
+}
+
+macro_rules! event_at {
+    ($level:expr, $($args:tt)+) => {
+        match $level {
+            Level::ERROR => tracing::event!(Level::ERROR, $($args)+),
+            Level::WARN => tracing::event!(Level::WARN, $($args)+),
+            Level::INFO => tracing::event!(Level::INFO, $($args)+),
+            Level::DEBUG => tracing::event!(Level::DEBUG, $($args)+),
+            _ => tracing::event!(Level::TRACE, $($args)+),
+        }
+    };
+}
+
+impl Error {
+
+    /// Emits the error as an event inside `span`, at the level chosen by the
+    /// level policy.
+    ///
+    /// Since tracing field names are static, every path error is emitted as
+    /// a following event at the same level, carrying `error.code`,
+    /// `error.path` and `error.path_message`.
+    pub fn record(&self, span: &Span) {
+        let level = (*LEVEL_POLICY.read().unwrap())(self);
+        self.record_at(span, level);
+    }
+
+    pub fn record_at(&self, span: &Span, level: Level) {
+        let source = self.cause().map(|_| {
+            let mut chain = Vec::new();
+            let mut cause = self.cause();
+            while let Some(current) = cause {
+                chain.push(current.to_string());
+                cause = current.cause();
+            }
+            chain.join(" <- ")
+        });
+        let frames = if self.frames().is_empty() {
+            None
+        } else {
+            Some(self.frames().iter().map(ToString::to_string).collect::<Vec<_>>().join("\n"))
+        };
+        self.ensure_incident_id();
+        let annotations = if self.annotations().is_empty() {
+            None
+        } else {
+            Some(self.annotations().iter().map(|(k, v)| format!("{}={}", k, v)).collect::<Vec<_>>().join(", "))
+        };
+        let contexts = if self.contexts().is_empty() {
+            None
+        } else {
+            Some(self.contexts().iter().rev().map(String::as_str).collect::<Vec<_>>().join(": "))
+        };
+        let created_at = self.created_at().map(crate::incident::format_timestamp);
+        span.in_scope(|| {
+            event_at!(
+                level,
+                error.code = self.code,
+                error.title = %self.inferred_title(),
+                error.message = %self.message,
+                error.internal_message = self.internal_message(),
+                error.source = source.as_deref(),
+                error.frames = frames.as_deref(),
+                error.incident_id = self.generated_incident_id(),
+                error.created_at = created_at.as_deref(),
+                error.annotations = annotations.as_deref(),
+                error.contexts = contexts.as_deref(),
+                "{}",
+                self,
+            );
+            if let Some(errors) = self.errors() {
+                for (path, message) in errors {
+                    event_at!(
+                        level,
+                        error.code = self.code,
+                        error.path = %path,
+                        error.path_message = %message,
+                    );
+                }
+            }
+        });
+    }
+}
@@ -0,0 +1,14 @@
+use teo_result::{envelope, Error, ErrorSerializable};
+
+#[test]
+fn captures_only_for_constructed_server_errors() {
+    // read once per process, so set before anything captures
+    std::env::set_var("RUST_LIB_BACKTRACE", "1");
+    let error = Error::internal_server_error();
+    assert!(error.backtrace().is_some());
+    assert!(Error::not_found().backtrace().is_none());
+    let json = serde_json::to_string(&error).unwrap();
+    assert!(serde_json::from_str::<Error>(&json).unwrap().backtrace().is_none());
+    assert!(envelope::decode(&envelope::encode(&error)).unwrap().backtrace().is_none());
+    assert!(Error::from(ErrorSerializable::from_error(&error)).backtrace().is_none());
+}
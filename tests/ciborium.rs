@@ -0,0 +1,42 @@
+#![cfg(feature = "ciborium")]
+
+use std::time::Duration;
+use indexmap::IndexMap;
+use teo_result::Error;
+
+fn errors() -> IndexMap<String, String> {
+    IndexMap::from([
+        ("user.name".to_owned(), "too short".to_owned()),
+        ("user.addresses.0.city".to_owned(), "value is required".to_owned()),
+        ("user.tags.2".to_owned(), "duplicated".to_owned()),
+    ])
+}
+
+#[test]
+fn round_trips_nested_paths() {
+    let error = Error::new_with_code_errors("value is invalid", 400, errors());
+    let decoded = Error::from_cbor(&error.to_cbor().unwrap()).unwrap();
+    assert_eq!(decoded.code, 400);
+    assert_eq!(decoded.message(), "value is invalid");
+    assert_eq!(decoded.errors(), Some(&errors()));
+    assert_eq!(decoded.errors().unwrap().keys().collect::<Vec<_>>(), errors().keys().collect::<Vec<_>>());
+}
+
+#[test]
+fn round_trips_codes() {
+    for error in [Error::not_found(), Error::conflict_message("exists"), Error::internal_server_error(), Error::new_with_code("odd", 600), Error::too_many_requests(Duration::from_secs(30))] {
+        let decoded = Error::from_cbor(&error.to_cbor().unwrap()).unwrap();
+        assert_eq!(decoded.code, error.code);
+        assert_eq!(decoded.message(), error.message());
+        assert_eq!(decoded.errors(), None);
+        assert_eq!(decoded.retry_after(), error.retry_after());
+    }
+}
+
+#[test]
+fn keeps_incident_id() {
+    let error = Error::internal_server_error();
+    let incident_id = error.incident_id().to_owned();
+    let decoded = Error::from_cbor(&error.to_cbor().unwrap()).unwrap();
+    assert_eq!(decoded.generated_incident_id(), Some(incident_id.as_str()));
+}
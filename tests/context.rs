@@ -0,0 +1,44 @@
+use teo_result::{context, envelope, Error, Lossless};
+
+fn request_id(error: &Error) -> Option<&str> {
+    error.annotations().get("request_id").map(String::as_str)
+}
+
+#[test]
+fn annotates_decoded_errors() {
+    let encoded = envelope::encode(&Error::not_found());
+    let error = context::sync_scope([("request_id", "r1")], || envelope::decode(&encoded).unwrap());
+    assert_eq!(request_id(&error), Some("r1"));
+    let json = serde_json::to_string(&Error::not_found()).unwrap();
+    let error = context::sync_scope([("request_id", "r1")], || serde_json::from_str::<Error>(&json).unwrap());
+    assert_eq!(request_id(&error), Some("r1"));
+}
+
+#[test]
+fn decoded_annotations_win() {
+    let mut error = Error::not_found();
+    error.annotate("request_id", "remote");
+    let json = serde_json::to_string(&Lossless(&error)).unwrap();
+    let error = context::sync_scope([("request_id", "local"), ("user", "42")], || serde_json::from_str::<Error>(&json).unwrap());
+    assert_eq!(request_id(&error), Some("remote"));
+    assert_eq!(error.annotations().get("user").map(String::as_str), Some("42"));
+}
+
+#[test]
+fn annotating_leaves_scope_untouched() {
+    context::sync_scope([("request_id", "r1")], || {
+        let mut error = Error::not_found();
+        error.annotate("user", "42");
+        assert_eq!(error.annotations().len(), 2);
+        assert_eq!(context::current().unwrap().len(), 1);
+        assert_eq!(Error::not_found().annotations().len(), 1);
+    });
+}
+
+#[cfg(feature = "bson")]
+#[test]
+fn annotates_bson_decoded_errors() {
+    let document = Error::not_found().to_bson_document().unwrap();
+    let error = context::sync_scope([("request_id", "r1")], || Error::from_bson_document(document).unwrap());
+    assert_eq!(request_id(&error), Some("r1"));
+}
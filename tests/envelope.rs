@@ -0,0 +1,56 @@
+use teo_result::{envelope, Error, Lossless};
+
+#[test]
+fn round_trips() {
+    let error = Error::invalid_request_pathed("user.name", "too short");
+    let decoded = envelope::decode(&envelope::encode(&error)).unwrap();
+    assert_eq!(decoded.code, 400);
+    assert_eq!(decoded.errors(), error.errors());
+    let decoded = envelope::decode_bytes(&envelope::encode_bytes(&error)).unwrap();
+    assert_eq!(decoded.errors(), error.errors());
+}
+
+#[test]
+fn accepts_unversioned_and_current_versions() {
+    assert_eq!(envelope::decode(r#"TeoError: {"code":404,"message":"not found"}"#).unwrap().code, 404);
+    let current = format!(r#"TeoError: {{"version":{},"code":404,"message":"not found"}}"#, envelope::VERSION);
+    assert_eq!(envelope::decode(&current).unwrap().code, 404);
+}
+
+#[test]
+fn rejects_newer_versions() {
+    let newer = format!(r#"TeoError: {{"version":{},"code":404,"message":"not found"}}"#, envelope::VERSION + 1);
+    assert!(envelope::is_envelope(&newer));
+    assert!(envelope::decode(&newer).is_none());
+    let mut bytes = envelope::encode_bytes(&Error::not_found());
+    bytes[3] += 1;
+    assert!(envelope::decode_bytes(&bytes).is_none());
+}
+
+#[test]
+fn keeps_internal_details_out_of_public_envelopes() {
+    let mut error = Error::internal_server_error().with_internal_message("connection refused");
+    error.annotate("user", "42");
+    let public = envelope::encode(&error);
+    assert!(!public.contains("connection refused"));
+    assert!(!public.contains("user"));
+    let decoded = envelope::decode(&envelope::encode_internal(&error)).unwrap();
+    assert_eq!(decoded.internal_message(), Some("connection refused"));
+    assert_eq!(decoded.annotations().get("user").map(String::as_str), Some("42"));
+}
+
+#[test]
+fn serializes_public_fields_unless_lossless() {
+    let mut error = Error::conflict_message("exists").with_internal_message("duplicate key users_email");
+    error.annotate("user", "42");
+    error.assign_cause(Error::internal_server_error_message("constraint violated"));
+    let public = serde_json::to_string(&error).unwrap();
+    assert!(!public.contains("duplicate key"));
+    assert!(!public.contains("user"));
+    assert!(!public.contains("constraint violated"));
+    assert_eq!(serde_json::from_str::<Error>(&public).unwrap().message(), "exists");
+    let decoded = serde_json::from_str::<Error>(&serde_json::to_string(&Lossless(&error)).unwrap()).unwrap();
+    assert_eq!(decoded.internal_message(), Some("duplicate key users_email"));
+    assert_eq!(decoded.annotations().get("user").map(String::as_str), Some("42"));
+    assert_eq!(decoded.cause().unwrap().message(), "constraint violated");
+}
@@ -0,0 +1,59 @@
+#![cfg(feature = "ffi")]
+
+use std::env;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+fn manifest_dir() -> &'static Path {
+    Path::new(env!("CARGO_MANIFEST_DIR"))
+}
+
+#[test]
+fn header_is_up_to_date() {
+    let config = cbindgen::Config::from_file(manifest_dir().join("cbindgen.toml")).unwrap();
+    let mut generated = Vec::new();
+    cbindgen::Builder::new()
+        .with_crate(manifest_dir())
+        .with_config(config)
+        .generate()
+        .unwrap()
+        .write(&mut generated);
+    let committed = std::fs::read_to_string(manifest_dir().join("include/teo_result.h")).unwrap();
+    assert!(
+        String::from_utf8(generated).unwrap() == committed,
+        "include/teo_result.h is stale, regenerate it with `cbindgen --config cbindgen.toml --crate teo-result --output include/teo_result.h`",
+    );
+}
+
+// builds the library as a static archive and runs `tests/ffi/smoke.c`
+// against it, skipped if no C compiler is installed
+#[test]
+fn c_smoke_test() {
+    let compiler = env::var("CC").unwrap_or_else(|_| "cc".to_owned());
+    if Command::new(&compiler).arg("--version").output().is_err() {
+        eprintln!("skipping C smoke test, `{}` is not available", compiler);
+        return;
+    }
+    let target_dir = PathBuf::from(env!("CARGO_TARGET_TMPDIR")).join("ffi");
+    let status = Command::new(env::var("CARGO").unwrap_or_else(|_| "cargo".to_owned()))
+        .current_dir(manifest_dir())
+        .args(["rustc", "--lib", "--features", "ffi", "--crate-type", "staticlib", "--target-dir"])
+        .arg(&target_dir)
+        .status()
+        .unwrap();
+    assert!(status.success());
+    let executable = target_dir.join("smoke");
+    let output = Command::new(&compiler)
+        .arg(manifest_dir().join("tests/ffi/smoke.c"))
+        .arg("-I")
+        .arg(manifest_dir().join("include"))
+        .arg(target_dir.join("debug/libteo_result.a"))
+        .args(["-lpthread", "-ldl", "-lm"])
+        .arg("-o")
+        .arg(&executable)
+        .output()
+        .unwrap();
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+    let output = Command::new(&executable).output().unwrap();
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+}
@@ -0,0 +1,49 @@
+#include <stdio.h>
+#include <string.h>
+#include "teo_result.h"
+
+#define CHECK(condition) do { if (!(condition)) { fprintf(stderr, "%s:%d: %s\n", __FILE__, __LINE__, #condition); return 1; } } while (0)
+
+int main(void) {
+    struct TeoError *error = teo_error_new("value is invalid", 400);
+    CHECK(error != NULL);
+    CHECK(teo_error_code(error) == 400);
+    CHECK(strcmp(teo_error_message(error), "value is invalid") == 0);
+    CHECK(teo_error_errors_len(error) == 0);
+
+    teo_error_insert_error(error, "user.name", "too short");
+    CHECK(teo_error_errors_len(error) == 1);
+    CHECK(strcmp(teo_error_errors_key(error, 0), "user.name") == 0);
+    CHECK(strcmp(teo_error_errors_value(error, 0), "too short") == 0);
+    CHECK(teo_error_errors_key(error, 1) == NULL);
+
+    char *json = teo_error_to_json(error);
+    struct TeoError *decoded = teo_error_from_json(json);
+    CHECK(decoded != NULL);
+    CHECK(teo_error_code(decoded) == 400);
+    CHECK(strcmp(teo_error_errors_value(decoded, 0), "too short") == 0);
+    teo_string_free(json);
+    teo_error_free(decoded);
+
+    char *envelope = teo_error_to_envelope(error);
+    decoded = teo_error_from_envelope(envelope);
+    CHECK(decoded != NULL);
+    CHECK(strcmp(teo_error_message(decoded), "value is invalid") == 0);
+    teo_string_free(envelope);
+    teo_error_free(decoded);
+    teo_error_free(error);
+
+    CHECK(teo_error_code(NULL) == 0);
+    CHECK(teo_error_message(NULL) == NULL);
+    CHECK(teo_error_errors_len(NULL) == 0);
+    CHECK(teo_error_errors_key(NULL, 0) == NULL);
+    CHECK(teo_error_errors_value(NULL, 0) == NULL);
+    CHECK(teo_error_to_json(NULL) == NULL);
+    CHECK(teo_error_from_json("not json") == NULL);
+    CHECK(teo_error_from_envelope("not an envelope") == NULL);
+    teo_error_free(NULL);
+    teo_string_free(NULL);
+
+    puts("ok");
+    return 0;
+}
@@ -0,0 +1,37 @@
+#![cfg(feature = "http")]
+
+use std::time::Duration;
+use http::Response;
+use http::header::{CONTENT_TYPE, RETRY_AFTER};
+use teo_result::{Error, ErrorSerializable};
+
+#[test]
+fn rounds_retry_after_up() {
+    let error = Error::too_many_requests(Duration::from_millis(300));
+    assert_eq!(ErrorSerializable::from_error(&error).retry_after, Some(1));
+    let response = Response::<String>::from(error);
+    assert_eq!(response.headers()[RETRY_AFTER], "1");
+    let response = Response::<String>::from(Error::service_unavailable(Duration::from_secs(2)));
+    assert_eq!(response.headers()[RETRY_AFTER], "2");
+}
+
+#[test]
+fn normalizes_header_names() {
+    let error = Error::unauthorized_with_challenge("Bearer", "api").with_header("X-Request-Id", "r1").with_header("x-request-id", "r2");
+    assert_eq!(error.headers().keys().collect::<Vec<_>>(), vec!["www-authenticate", "x-request-id"]);
+    assert_eq!(error.headers()["x-request-id"], "r2");
+    let decoded: Error = serde_json::from_str(r#"{"code":405,"message":"method not allowed","headers":{"Allow":"GET"}}"#).unwrap();
+    assert_eq!(decoded.headers()["allow"], "GET");
+    let response = Response::<String>::from(error);
+    assert_eq!(response.headers()["www-authenticate"], "Bearer realm=\"api\"");
+}
+
+#[test]
+fn keeps_content_type_and_retry_after() {
+    let error = Error::too_many_requests(Duration::from_secs(5)).with_header("Content-Type", "text/plain").with_header("Retry-After", "60");
+    let response = Response::<String>::from(error);
+    assert_eq!(response.headers()[CONTENT_TYPE], "application/json");
+    assert_eq!(response.headers()[RETRY_AFTER], "5");
+    let response = Response::<String>::from(Error::too_many_requests(None).with_header("Retry-After", "60"));
+    assert_eq!(response.headers()[RETRY_AFTER], "60");
+}
@@ -0,0 +1,32 @@
+use serde_json::Value;
+use teo_result::{Error, ErrorSerializable};
+use teo_result::incident::{self, IncidentMode};
+
+fn logged_incident_id(error: &Error) -> String {
+    let log = format!("{:?}", error);
+    let json = log.lines().next().unwrap().strip_prefix("teo_result::Error: ").unwrap();
+    serde_json::from_str::<Value>(json).unwrap()["incident_id"].as_str().unwrap().to_owned()
+}
+
+fn sent_incident_id(error: &Error) -> String {
+    serde_json::from_str::<Value>(&ErrorSerializable::error_string(error)).unwrap()["incident_id"].as_str().unwrap().to_owned()
+}
+
+#[test]
+fn clones_share_the_incident_id() {
+    incident::set_mode(IncidentMode::ServerErrors);
+    let error = Error::internal_server_error_message("database unreachable");
+    let copy = error.clone();
+    let incident_id = logged_incident_id(&copy);
+    assert_eq!(sent_incident_id(&error), incident_id);
+    assert_eq!(logged_incident_id(&error), incident_id);
+    assert_eq!(sent_incident_id(&copy), incident_id);
+}
+
+#[test]
+fn uncovered_errors_have_no_incident_id() {
+    incident::set_mode(IncidentMode::ServerErrors);
+    let error = Error::not_found();
+    assert_eq!(error.clone().generated_incident_id(), None);
+    assert_eq!(error.created_at(), None);
+}
@@ -0,0 +1,50 @@
+#![cfg(feature = "opentelemetry")]
+
+use opentelemetry::{KeyValue, Value};
+use opentelemetry::trace::{Status, Tracer, TracerProvider};
+use opentelemetry_sdk::trace::{InMemorySpanExporter, SdkTracerProvider, SpanData};
+use teo_result::Error;
+
+fn record(f: impl FnOnce(&opentelemetry_sdk::trace::SdkTracer)) -> SpanData {
+    let exporter = InMemorySpanExporter::default();
+    let provider = SdkTracerProvider::builder().with_simple_exporter(exporter.clone()).build();
+    f(&provider.tracer("teo-result"));
+    let mut spans = exporter.get_finished_spans().unwrap();
+    assert_eq!(spans.len(), 1);
+    spans.pop().unwrap()
+}
+
+fn attribute<'a>(attributes: &'a [KeyValue], key: &str) -> Option<&'a Value> {
+    attributes.iter().find(|attribute| attribute.key.as_str() == key).map(|attribute| &attribute.value)
+}
+
+#[test]
+fn records_exception_event_on_span() {
+    let span = record(|tracer| {
+        let mut span = tracer.start("request");
+        Error::internal_server_error_message("database is down").record_otel_exception_on(&mut span);
+    });
+    assert_eq!(span.events.events.len(), 1);
+    let event = &span.events.events[0];
+    assert_eq!(event.name, "exception");
+    assert_eq!(attribute(&event.attributes, "exception.type"), Some(&Value::from("InternalServerError")));
+    assert_eq!(attribute(&event.attributes, "exception.message"), Some(&Value::from("database is down")));
+    assert_eq!(attribute(&event.attributes, "http.response.status_code"), Some(&Value::I64(500)));
+    assert_eq!(span.status, Status::error("database is down"));
+}
+
+#[test]
+fn records_exception_event_on_active_span() {
+    let span = record(|tracer| {
+        tracer.in_span("request", |_| {
+            let mut error = Error::not_found();
+            error.annotate("user", "42");
+            error.record_otel_exception();
+        });
+    });
+    let event = &span.events.events[0];
+    assert_eq!(attribute(&event.attributes, "exception.type"), Some(&Value::from("NotFound")));
+    assert_eq!(attribute(&event.attributes, "http.response.status_code"), Some(&Value::I64(404)));
+    assert_eq!(attribute(&event.attributes, "teo.error.annotation.user"), Some(&Value::from("42")));
+    assert_eq!(span.status, Status::Unset);
+}
@@ -0,0 +1,58 @@
+#![cfg(feature = "prost")]
+
+use teo_result::Error;
+
+// `google.rpc.Status` { code, message, details: [Any { type_url, value }] }
+const NOT_FOUND: &[u8] = b"\
+    \x08\x05\
+    \x12\x09not found\
+    \x1a\x56\
+        \x0a\x28type.googleapis.com/google.rpc.ErrorInfo\
+        \x12\x2a\
+            \x0a\x09NOT_FOUND\
+            \x12\x09teodev.io\
+            \x1a\x12\x0a\x0bhttp_status\x12\x03404";
+
+const BAD_REQUEST: &[u8] = b"\
+    \x08\x03\
+    \x12\x10value is invalid\
+    \x1a\x58\
+        \x0a\x28type.googleapis.com/google.rpc.ErrorInfo\
+        \x12\x2c\
+            \x0a\x0bBAD_REQUEST\
+            \x12\x09teodev.io\
+            \x1a\x12\x0a\x0bhttp_status\x12\x03400\
+    \x1a\x45\
+        \x0a\x29type.googleapis.com/google.rpc.BadRequest\
+        \x12\x18\
+            \x0a\x16\x0a\x09user.name\x12\x09too short";
+
+const UNREGISTERED: &[u8] = b"\
+    \x08\x0d\
+    \x12\x03odd\
+    \x1a\x55\
+        \x0a\x28type.googleapis.com/google.rpc.ErrorInfo\
+        \x12\x29\
+            \x0a\x08HTTP_600\
+            \x12\x09teodev.io\
+            \x1a\x12\x0a\x0bhttp_status\x12\x03600";
+
+#[test]
+fn encodes_golden_bytes() {
+    assert_eq!(Error::not_found().to_protobuf(), NOT_FOUND);
+    assert_eq!(Error::invalid_request_pathed("user.name", "too short").to_protobuf(), BAD_REQUEST);
+    assert_eq!(Error::new_with_code("odd", 600).to_protobuf(), UNREGISTERED);
+}
+
+#[test]
+fn decodes_golden_bytes() {
+    let error = Error::from_protobuf(NOT_FOUND).unwrap();
+    assert_eq!(error.code, 404);
+    assert_eq!(error.message(), "not found");
+    assert!(error.errors().is_none());
+    let error = Error::from_protobuf(BAD_REQUEST).unwrap();
+    assert_eq!(error.code, 400);
+    assert_eq!(error.errors().unwrap().get("user.name").unwrap(), "too short");
+    let error = Error::from_protobuf(UNREGISTERED).unwrap();
+    assert_eq!(error.code, 600);
+}
@@ -0,0 +1,78 @@
+use indexmap::indexmap;
+use teo_result::{envelope, Error, ErrorSerializable};
+use teo_result::redaction::{self, RedactionPolicy};
+
+fn logged_incident_id(error: &Error) -> String {
+    let log = format!("{:?}", error);
+    let json = log.lines().next().unwrap().strip_prefix("teo_result::Error: ").unwrap();
+    serde_json::from_str::<serde_json::Value>(json).unwrap()["incident_id"].as_str().unwrap().to_owned()
+}
+
+#[test]
+fn production_redacts_server_errors() {
+    let error = Error::internal_server_error_pathed("db.host", "10.0.0.1 unreachable").with_internal_message("connection refused");
+    let public = ErrorSerializable::from_error_with_policy(&error, &RedactionPolicy::production());
+    assert_eq!(public.code, 500);
+    assert_eq!(public.message, "Internal Server Error");
+    assert!(public.errors.is_null());
+    assert!(public.incident_id.is_some());
+}
+
+#[test]
+fn production_keeps_client_errors() {
+    let error = Error::not_found_message("user not found").with_internal_message("no row with id 42");
+    let view = RedactionPolicy::production().apply(&error);
+    assert_eq!(view.message(), "user not found");
+    assert_eq!(view.internal_message(), None);
+}
+
+#[test]
+fn matches_wildcards() {
+    let policy = RedactionPolicy { sensitive_paths: vec!["*.password".to_owned(), "card.*".to_owned()], ..RedactionPolicy::disabled() };
+    assert!(policy.is_sensitive("user.password"));
+    assert!(policy.is_sensitive("users.0.password"));
+    assert!(policy.is_sensitive("card.number"));
+    assert!(!policy.is_sensitive("password"));
+    assert!(!policy.is_sensitive("user.password_hint"));
+    assert!(!policy.is_sensitive("cards.number"));
+}
+
+#[test]
+fn masks_client_error_paths() {
+    let error = Error::new_with_code_errors("value is invalid", 400, indexmap! {
+        "user.password".to_owned() => "hunter2 is too short".to_owned(),
+        "user.name".to_owned() => "too short".to_owned(),
+    });
+    let view = RedactionPolicy::production().apply(&error);
+    assert_eq!(view.message(), "value is invalid");
+    let errors = view.errors().unwrap();
+    assert_eq!(errors.get("user.password").map(String::as_str), Some("[REDACTED]"));
+    assert_eq!(errors.get("user.name").map(String::as_str), Some("too short"));
+}
+
+#[test]
+fn logs_and_public_views_share_the_incident_id() {
+    let error = Error::internal_server_error_message("database unreachable");
+    let public = ErrorSerializable::from_error_with_policy(&error, &RedactionPolicy::production());
+    assert_eq!(public.incident_id, Some(logged_incident_id(&error)));
+}
+
+#[test]
+fn redacts_public_envelopes() {
+    redaction::set_global_policy(RedactionPolicy::production());
+    let error = Error::invalid_request_pathed("user.password", "hunter2 is too short");
+    let encoded = envelope::encode(&error);
+    assert!(!encoded.contains("hunter2"));
+    assert_eq!(envelope::decode(&encoded).unwrap().errors().unwrap().get("user.password").map(String::as_str), Some("[REDACTED]"));
+    let mut error = Error::invalid_request_message("could not save");
+    error.assign_cause(Error::internal_server_error_message("database unreachable"));
+    let encoded = envelope::encode(&error);
+    assert!(!encoded.contains("database unreachable"));
+    assert_eq!(envelope::decode(&encoded).unwrap().cause().unwrap().message(), "Internal Server Error");
+    let error = Error::internal_server_error_message("database unreachable");
+    let decoded = envelope::decode_bytes(&envelope::encode_bytes(&error)).unwrap();
+    assert_eq!(decoded.message(), "Internal Server Error");
+    assert_eq!(decoded.generated_incident_id(), Some(logged_incident_id(&error).as_str()));
+    let decoded = envelope::decode(&envelope::encode_internal(&error)).unwrap();
+    assert_eq!(decoded.message(), "database unreachable");
+}
@@ -0,0 +1,44 @@
+use serde_json::Value;
+use teo_result::Error;
+use teo_result::render::RenderFormat;
+
+#[test]
+fn negotiates_browser_accept_headers() {
+    let chrome = "text/html,application/xhtml+xml,application/xml;q=0.9,image/avif,image/webp,image/apng,*/*;q=0.8,application/signed-exchange;v=b3;q=0.7";
+    assert_eq!(RenderFormat::negotiate(chrome), RenderFormat::Html);
+    let firefox = "text/html,application/xhtml+xml,application/xml;q=0.9,*/*;q=0.8";
+    assert_eq!(RenderFormat::negotiate(firefox), RenderFormat::Html);
+    assert_eq!(RenderFormat::negotiate("*/*"), RenderFormat::Json);
+    assert_eq!(RenderFormat::negotiate(""), RenderFormat::Json);
+    assert_eq!(RenderFormat::negotiate("image/png"), RenderFormat::Json);
+}
+
+#[test]
+fn orders_by_quality() {
+    assert_eq!(RenderFormat::negotiate("application/xml;q=0.5, application/problem+json"), RenderFormat::ProblemJson);
+    assert_eq!(RenderFormat::negotiate("text/html;q=0.1, text/plain;q=0.8, application/xml;q=0.5"), RenderFormat::PlainText);
+    // equal weights keep the client's order
+    assert_eq!(RenderFormat::negotiate("text/plain;q=0.9, application/json;q=0.9"), RenderFormat::PlainText);
+    assert_eq!(RenderFormat::negotiate("text/html;q=0, text/xml"), RenderFormat::Xml);
+    assert_eq!(RenderFormat::negotiate("TEXT/HTML"), RenderFormat::Html);
+}
+
+#[test]
+fn escapes_path_keys() {
+    let error = Error::invalid_request_pathed("<script>\"name\"", "<b>too short</b>");
+    let html = error.render_as(RenderFormat::Html).body;
+    assert!(html.contains("<code>&lt;script&gt;&quot;name&quot;</code>: &lt;b&gt;too short&lt;/b&gt;"));
+    assert!(!html.contains("<script>"));
+    let xml = error.render_as(RenderFormat::Xml).body;
+    assert!(xml.contains("<error path=\"&lt;script&gt;&quot;name&quot;\">&lt;b&gt;too short&lt;/b&gt;</error>"));
+}
+
+#[test]
+fn titles_unregistered_codes_by_class() {
+    let problem = |error: Error| serde_json::from_str::<Value>(&error.render_as(RenderFormat::ProblemJson).body).unwrap();
+    assert_eq!(problem(Error::not_found())["title"], "Not Found");
+    assert_eq!(problem(Error::new_with_code("odd", 600))["title"], "Error");
+    assert_eq!(problem(Error::new_with_code("odd", 599))["title"], "Server Error");
+    assert_eq!(problem(Error::new_with_code("odd", 499))["title"], "Client Error");
+    assert_eq!(Error::new_with_code("odd", 600).render_as(RenderFormat::PlainText).body, "600 Error: odd\n");
+}
@@ -0,0 +1,163 @@
+#![cfg(feature = "tokio")]
+
+use std::future::{ready, Future};
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use teo_result::{Error, Result};
+use teo_result::retry::{retry, Attempts, Clock, RetryPolicy};
+
+// advances virtual time instead of sleeping and records every sleep
+#[derive(Clone)]
+struct MockClock {
+    now: Arc<Mutex<Instant>>,
+    sleeps: Arc<Mutex<Vec<Duration>>>,
+}
+
+impl MockClock {
+
+    fn new() -> Self {
+        Self { now: Arc::new(Mutex::new(Instant::now())), sleeps: Arc::new(Mutex::new(Vec::new())) }
+    }
+
+    fn sleeps(&self) -> Vec<Duration> {
+        self.sleeps.lock().unwrap().clone()
+    }
+}
+
+impl Clock for MockClock {
+
+    fn now(&self) -> Instant {
+        *self.now.lock().unwrap()
+    }
+
+    fn sleep(&self, duration: Duration) -> Pin<Box<dyn Future<Output = ()> + Send>> {
+        *self.now.lock().unwrap() += duration;
+        self.sleeps.lock().unwrap().push(duration);
+        Box::pin(ready(()))
+    }
+}
+
+// fails with the errors in order, then succeeds
+fn run(policy: &RetryPolicy, errors: Vec<Error>) -> (Result<u32>, u32) {
+    let errors = Mutex::new(errors.into_iter());
+    let calls = Mutex::new(0);
+    let runtime = tokio::runtime::Builder::new_current_thread().enable_time().build().unwrap();
+    let result = runtime.block_on(retry(policy, || {
+        *calls.lock().unwrap() += 1;
+        let result = match errors.lock().unwrap().next() {
+            Some(error) => Err(error),
+            None => Ok(*calls.lock().unwrap()),
+        };
+        ready(result)
+    }));
+    let calls = *calls.lock().unwrap();
+    (result, calls)
+}
+
+fn policy(clock: &MockClock) -> RetryPolicy {
+    RetryPolicy::new().max_attempts(5).initial_delay(Duration::from_secs(1)).jitter(false).clock(clock.clone())
+}
+
+#[test]
+fn retries_until_success() {
+    let clock = MockClock::new();
+    let (result, calls) = run(&policy(&clock), vec![Error::service_unavailable(None), Error::bad_gateway()]);
+    assert_eq!(result.unwrap(), 3);
+    assert_eq!(calls, 3);
+    assert_eq!(clock.sleeps(), vec![Duration::from_secs(1), Duration::from_secs(2)]);
+}
+
+#[test]
+fn stops_on_non_retryable_error() {
+    let clock = MockClock::new();
+    let (result, calls) = run(&policy(&clock), vec![Error::service_unavailable(None), Error::not_found(), Error::service_unavailable(None)]);
+    let error = result.unwrap_err();
+    assert_eq!(calls, 2);
+    assert_eq!(error.code, 404);
+    assert!(error.errors().is_none());
+    assert_eq!(error.cause().unwrap().code, 404);
+    assert_eq!(error.extensions().get::<Attempts>().unwrap().0.iter().map(|error| error.code).collect::<Vec<_>>(), vec![503, 404]);
+    assert_eq!(clock.sleeps(), vec![Duration::from_secs(1)]);
+}
+
+#[test]
+fn returns_only_attempt_as_is() {
+    let clock = MockClock::new();
+    let (result, calls) = run(&policy(&clock), vec![Error::invalid_request_pathed("name", "too short")]);
+    let error = result.unwrap_err();
+    assert_eq!(calls, 1);
+    assert_eq!(error.errors().unwrap().get("name").unwrap(), "too short");
+    assert!(clock.sleeps().is_empty());
+}
+
+#[test]
+fn honors_retry_after() {
+    let clock = MockClock::new();
+    let (result, _) = run(&policy(&clock), vec![Error::too_many_requests(Duration::from_secs(7)), Error::service_unavailable(None)]);
+    assert!(result.is_ok());
+    assert_eq!(clock.sleeps(), vec![Duration::from_secs(7), Duration::from_secs(2)]);
+}
+
+#[test]
+fn gives_up_when_retry_after_exceeds_max_delay() {
+    let clock = MockClock::new();
+    let policy = policy(&clock).max_delay(Duration::from_secs(5));
+    let (result, calls) = run(&policy, vec![Error::too_many_requests(Duration::from_secs(3600))]);
+    let error = result.unwrap_err();
+    assert_eq!(calls, 1);
+    assert_eq!(error.retry_after(), Some(Duration::from_secs(3600)));
+    assert!(clock.sleeps().is_empty());
+}
+
+#[test]
+fn gives_up_when_retry_after_exceeds_time_left() {
+    let clock = MockClock::new();
+    let policy = policy(&clock).max_elapsed(Duration::from_secs(5));
+    let (result, calls) = run(&policy, vec![Error::service_unavailable(None), Error::service_unavailable(Duration::from_secs(6))]);
+    let error = result.unwrap_err();
+    assert_eq!(calls, 2);
+    assert_eq!(error.retry_after(), Some(Duration::from_secs(6)));
+    assert_eq!(clock.sleeps(), vec![Duration::from_secs(1)]);
+}
+
+#[test]
+fn gives_up_at_max_elapsed() {
+    let clock = MockClock::new();
+    let policy = policy(&clock).max_elapsed(Duration::from_secs(5));
+    let errors = (0..5).map(|_| Error::service_unavailable(None)).collect();
+    let (result, calls) = run(&policy, errors);
+    let error = result.unwrap_err();
+    // sleeping 1s and 2s fits, another 4s would pass the limit
+    assert_eq!(calls, 3);
+    assert_eq!(clock.sleeps(), vec![Duration::from_secs(1), Duration::from_secs(2)]);
+    assert_eq!(error.message(), "operation failed after 3 attempts");
+    assert_eq!(error.extensions().get::<Attempts>().unwrap().0.len(), 3);
+}
+
+#[test]
+fn renders_attempts() {
+    let clock = MockClock::new();
+    let policy = policy(&clock).max_attempts(2);
+    let (result, _) = run(&policy, vec![Error::service_unavailable(None), Error::bad_gateway()]);
+    let error = result.unwrap_err();
+    let lines = ["attempt 1: 503 service unavailable", "attempt 2: 502 bad gateway"];
+    for rendered in [format!("{:?}", error), format!("{:#}", error)] {
+        let found: Vec<&str> = rendered.lines().map(str::trim).filter(|line| line.starts_with("attempt ")).collect();
+        assert_eq!(found, lines);
+    }
+    #[cfg(feature = "report")]
+    assert!(lines.iter().all(|line| error.report().color(false).to_string().contains(line)));
+}
+
+#[test]
+fn classifier_overrides_retryability() {
+    let clock = MockClock::new();
+    let policy = policy(&clock).classifier(|error| error.code == 409);
+    let (result, calls) = run(&policy, vec![Error::conflict(), Error::conflict()]);
+    assert_eq!(result.unwrap(), 3);
+    assert_eq!(calls, 3);
+    let (result, calls) = run(&policy, vec![Error::service_unavailable(None)]);
+    assert_eq!(result.unwrap_err().code, 503);
+    assert_eq!(calls, 1);
+}
@@ -0,0 +1,94 @@
+#![cfg(feature = "tracing")]
+
+use std::collections::HashMap;
+use std::fmt::Debug;
+use std::sync::{Arc, Mutex};
+use tracing::{Event, Id, Level, Metadata, Span, Subscriber};
+use tracing::field::{Field, Visit};
+use tracing::span::{Attributes, Record};
+use teo_result::{trace, Error, Result, ResultExt};
+
+type Captured = (Level, HashMap<&'static str, String>);
+
+#[derive(Clone, Default)]
+struct Capture(Arc<Mutex<Vec<Captured>>>);
+
+struct Fields(HashMap<&'static str, String>);
+
+impl Visit for Fields {
+    fn record_debug(&mut self, field: &Field, value: &dyn Debug) {
+        self.0.insert(field.name(), format!("{:?}", value));
+    }
+
+    fn record_str(&mut self, field: &Field, value: &str) {
+        self.0.insert(field.name(), value.to_owned());
+    }
+}
+
+impl Subscriber for Capture {
+    fn enabled(&self, _: &Metadata<'_>) -> bool {
+        true
+    }
+
+    fn new_span(&self, _: &Attributes<'_>) -> Id {
+        Id::from_u64(1)
+    }
+
+    fn record(&self, _: &Id, _: &Record<'_>) {}
+
+    fn record_follows_from(&self, _: &Id, _: &Id) {}
+
+    fn event(&self, event: &Event<'_>) {
+        let mut fields = Fields(HashMap::new());
+        event.record(&mut fields);
+        self.0.lock().unwrap().push((*event.metadata().level(), fields.0));
+    }
+
+    fn enter(&self, _: &Id) {}
+
+    fn exit(&self, _: &Id) {}
+}
+
+fn capture(f: impl FnOnce()) -> Vec<Captured> {
+    let capture = Capture::default();
+    tracing::subscriber::with_default(capture.clone(), f);
+    let events = capture.0.lock().unwrap().clone();
+    events
+}
+
+fn levels(events: &[Captured]) -> Vec<Level> {
+    events.iter().map(|(level, _)| *level).collect()
+}
+
+#[test]
+fn records_one_event_per_path() {
+    let mut error = Error::invalid_request_pathed("user.email", "invalid format");
+    error.errors.as_mut().unwrap().insert("user.name".to_owned(), "too short".to_owned());
+    let events = capture(|| error.record_at(&Span::current(), Level::WARN));
+    assert_eq!(levels(&events), [Level::WARN, Level::WARN, Level::WARN]);
+    let (_, main) = &events[0];
+    assert_eq!(main["error.code"], "400");
+    assert_eq!(main["error.message"], "value is invalid");
+    assert!(!main.contains_key("error.path"));
+    let paths: Vec<(&str, &str)> = events[1..].iter().map(|(_, fields)| (fields["error.path"].as_str(), fields["error.path_message"].as_str())).collect();
+    assert_eq!(paths, [("user.email", "invalid format"), ("user.name", "too short")]);
+    assert!(events[1..].iter().all(|(_, fields)| fields["error.code"] == "400"));
+}
+
+#[test]
+fn applies_the_level_policy() {
+    let events = capture(|| {
+        Error::internal_server_error().record(&Span::current());
+        Error::not_found().record(&Span::current());
+        Error::new_with_code("moved", 301).record(&Span::current());
+    });
+    assert_eq!(levels(&events), [Level::ERROR, Level::WARN, Level::DEBUG]);
+    trace::set_level_policy(|_| Level::INFO);
+    let events = capture(|| {
+        let result: Result<()> = Err(Error::not_found_pathed("user.id", "no such user"));
+        let _ = result.inspect_teo_err_trace();
+    });
+    trace::set_level_policy(trace::default_level);
+    assert_eq!(levels(&events), [Level::INFO, Level::INFO]);
+    assert_eq!(events[1].1["error.path"], "user.id");
+}